@@ -1,8 +1,13 @@
 use crate::components::{parse_day_from_str, styled_button, AocDayInput, AocInput, OwnInputManager};
+use crate::native_comparison_component::NativeComparison;
+use crate::performance_history_component::PerformanceHistoryView;
 use crate::run_tasks_component::RunTasks;
+use aoc_2024_wasm::benchmark::BenchmarkConfig;
+use aoc_2024_wasm::deep_link::DayLink;
+use aoc_2024_wasm::performance_history::{build_version, PerformanceEntry, PerformanceHistory, PerformanceRun, PERFORMANCE_HISTORY_STORAGE_KEY};
 use aoc_2024_wasm::testcases::Testcase;
 use aoc_2024_wasm::Part::{Part1, Part2};
-use aoc_2024_wasm::{solve_day, Part, Solution};
+use aoc_2024_wasm::{benchmark_day, solve_day, Part, Solution};
 use chrono::{DateTime, Utc};
 use codee::string::JsonSerdeCodec;
 use humantime::format_duration;
@@ -13,7 +18,7 @@ use leptos::prelude::*;
 use leptos::task::spawn_local;
 use leptos_meta::*;
 use leptos_router::components::{Outlet, ParentRoute};
-use leptos_router::hooks::use_params_map;
+use leptos_router::hooks::{use_navigate, use_params_map, use_query_map};
 use leptos_router::{
     components::{Route, Router, Routes, A},
     path,
@@ -45,6 +50,7 @@ pub fn App() -> impl IntoView {
     provide_context(testcases_by_day);
     let local_storage_key = "adventofcode-2024".to_string();
     let (all_real_input_files, _, _) = use_local_storage::<AocInput, JsonSerdeCodec>(local_storage_key.clone());
+    let (performance_history, set_performance_history, _) = use_local_storage::<PerformanceHistory, JsonSerdeCodec>(PERFORMANCE_HISTORY_STORAGE_KEY);
 
     view! {
         <Link rel="shortcut icon" type_="image/ico" href="/favicon.ico" />
@@ -70,9 +76,30 @@ pub fn App() -> impl IntoView {
                         <Route
                             path=path!("all-days-performance")
                             view=move || {
-                                view! { <RunAllComponent aoc_input_files=all_real_input_files /> }
+                                view! {
+                                    <RunAllComponent
+                                        aoc_input_files=all_real_input_files
+                                        performance_history=performance_history
+                                        set_performance_history=set_performance_history
+                                    />
+                                }
+                            }
+                        />
+                        <Route
+                            path=path!("performance-history")
+                            view=move || {
+                                view! {
+                                    <PerformanceHistoryView
+                                        performance_history=performance_history
+                                        set_performance_history=set_performance_history
+                                    />
+                                }
                             }
                         />
+                        <Route
+                            path=path!("native-vs-wasm")
+                            view=move || view! { <NativeComparison performance_history=performance_history /> }
+                        />
                         <Route
                             path=path!("day/:day")
                             view=move || {
@@ -105,15 +132,18 @@ async fn write_to_clipboard(text: String) {
 }
 
 #[component]
-fn AocTestcase(testcase: Testcase) -> impl IntoView {
+fn AocTestcase(testcase: Testcase, link: DayLink) -> impl IntoView {
     let part = match testcase.part {
         1 => Ok(Part1),
         2 => Ok(Part2),
         _ => Err("Let's not get too ambitions - two parts are enough ;-)"),
     };
 
+    // custom args from the url take precedence over the ones from testcases.toml
+    let args = link.args.clone().or(testcase.args.clone());
+
     let result = match part {
-        Ok(part) => Ok(solve_day(testcase.day, part, &testcase.input, testcase.args.clone())),
+        Ok(part) => Ok(solve_day(testcase.day, part, &testcase.input, args.clone())),
         Err(err) => Err(err),
     };
 
@@ -123,6 +153,8 @@ fn AocTestcase(testcase: Testcase) -> impl IntoView {
     };
 
     let testcase_input = testcase.input.clone();
+    let href = link.href();
+    let absolute_link = web_sys::window().and_then(|w| w.location().origin().ok()).unwrap_or_default() + &href;
 
     let duration = match result {
         Ok(res) => {
@@ -139,7 +171,8 @@ fn AocTestcase(testcase: Testcase) -> impl IntoView {
         p().child(span().class("font-bold").child("Actual Solution: ")).child(result_html),
         p().child(span().class("font-bold").child("Duration: ")).child(duration),
         //testcase.args.map(|args| p().child(span().class("font-bold").child("Custom Args: ")).child(args)),
-        testcase.args.map(|arg| p().child(span().class("font-bold").child("Custom Args: ")).child(span().child(arg))),
+        args.map(|arg| p().child(span().class("font-bold").child("Custom Args: ")).child(span().child(arg))),
+        p().child(view! { <A href=href>"Link to this testcase"</A> }),
         p().class("font-bold mt-4").child(span().child("Testdata:")),
         textarea()
             .readonly(true)
@@ -147,7 +180,10 @@ fn AocTestcase(testcase: Testcase) -> impl IntoView {
             .child(testcase.input)
             .rows(20)
             .cols(40),
-        styled_button().on(leptos::ev::click, move |_| spawn_local(write_to_clipboard(testcase_input.clone()))).child("Copy"),
+        div().class("flex flex-row gap-2").child((
+            styled_button().on(leptos::ev::click, move |_| spawn_local(write_to_clipboard(testcase_input.clone()))).child("Copy"),
+            styled_button().on(leptos::ev::click, move |_| spawn_local(write_to_clipboard(absolute_link.clone()))).child("Copy Link"),
+        )),
     ))
 }
 
@@ -161,43 +197,61 @@ fn parts_for_day(day: u32) -> Vec<Part> {
 
 #[component]
 fn AocDay(aoc_input_files: Signal<AocInput>) -> impl IntoView {
-    //let (real_inputs, _, _) = use_local_storage::<AocInput, codee::string::JsonSerdeCodec>("adventofcode-2024");
-
     let testcases_by_day = use_context::<ReadSignal<Vec<(u32, Vec<Testcase>)>>>().expect("to have found the testcases");
     let params = use_params_map();
-    let maybe_day = move || params.read().get("day");
-    let maybe_day_and_inputs = move || {
-        maybe_day().map(|day_str| {
-            let maybe_real_input = aoc_input_files.get().days.iter().find(|d| d.day.to_string() == day_str).cloned();
-            (day_str, maybe_real_input)
+    let query = use_query_map();
+
+    // The route component is reused when navigating between days, so everything below has to be derived from
+    // these memos - they change with the route params and query, which re-renders the day view.
+    let maybe_day = Memo::new(move |_| params.read().get("day").and_then(|day_str| parse_day_from_str(&day_str)));
+    let maybe_link = Memo::new(move |_| {
+        maybe_day.get().map(|day| {
+            let query = query.read();
+            DayLink::from_query(day, |key| query.get(key))
         })
-    };
+    });
 
     move || {
-        maybe_day_and_inputs().map(|(day_str, maybe_real_input)| {
-            let maybe_testcases_for_day = testcases_by_day.read().iter().find(|&(d, _)| d.to_string() == day_str).cloned();
-            let day = parse_day_from_str(&day_str).unwrap();
+        maybe_link.get().map(|link| {
+            let day = link.day;
+            let maybe_real_input = aoc_input_files.get().days.iter().find(|d| d.day == day).cloned();
+            let maybe_testcases_for_day = testcases_by_day.read().iter().find(|&(d, _)| *d == day).cloned();
 
-            //FIXME: this doesn't refresh when I navigate around by clicking links
-            // reloading the page with the route '/days/:day' _does_ work
             let part_divs = maybe_testcases_for_day.map(|(_, testcases)| {
                 testcases
-                    .iter()
+                    .into_iter()
                     .into_group_map_by(|tc| tc.part)
                     .into_iter()
                     .sorted_by_key(|tup| tup.0)
+                    .filter(|(part, _)| link.part.is_none_or(|p| p == *part))
                     .map(|(part, testcases)| {
+                        let num_testcases = testcases.len();
+                        let selected_testcases = testcases
+                            .into_iter()
+                            .enumerate()
+                            .filter(|(idx, _)| link.selects(part, *idx))
+                            .map(|(idx, tc)| {
+                                let testcase_link = DayLink::new(day).with_part(part).with_testcase(idx).with_args(link.args.clone()).with_step(link.step);
+                                AocTestcase(AocTestcaseProps {
+                                    testcase: tc,
+                                    link: testcase_link,
+                                })
+                            })
+                            .collect_view();
+
                         div()
                             .class("flex flex-col gap-4 divide-y")
-                            .child(h3().class("text-2xl font-bold").child(format!("Part {}: {} Testcase(s)", part, testcases.len())))
-                            .child(testcases.into_iter().map(|tc| AocTestcase(AocTestcaseProps { testcase: tc.clone() })).collect_view())
+                            .child(h3().class("text-2xl font-bold").child(format!("Part {}: {} Testcase(s)", part, num_testcases)))
+                            .child(selected_testcases)
                     })
                     .collect_view()
             });
 
-            let real_input_divs = maybe_real_input.clone().map(|inp| {
+            // the real input is only interesting if no specific testcase has been linked
+            let real_input_divs = maybe_real_input.clone().filter(|_| link.testcase.is_none()).map(|inp| {
                 parts_for_day(day)
                     .into_iter()
+                    .filter(|part| link.part.is_none_or(|p| p == part.clone() as u32))
                     .map(|part| {
                         log!("calculating result for real input for day {day} part {part:?}. Input: {}", inp.input);
                         let result = solve_day(day, part.clone(), inp.input.as_str(), None);
@@ -218,7 +272,8 @@ fn AocDay(aoc_input_files: Signal<AocInput>) -> impl IntoView {
             });
 
             div()
-                .child(h2().class("text-3xl font-bold").child(format!("AocDay - Day {:02}", day_str)))
+                .child(h2().class("text-3xl font-bold").child(format!("AocDay - Day {:02}", day)))
+                .child(DayLinkControls(DayLinkControlsProps { link: link.clone() }))
                 .child(div().class("flex flex-row gap-8 divide-x").child(part_divs).child(real_input_divs))
                 .child(
                     div()
@@ -238,6 +293,46 @@ fn AocDay(aoc_input_files: Signal<AocInput>) -> impl IntoView {
     }
 }
 
+/// Lets the user change the part filter and custom args - both end up in the url
+#[component]
+fn DayLinkControls(link: DayLink) -> impl IntoView {
+    let navigate = use_navigate();
+    let (args, set_args) = signal(link.args.clone().unwrap_or_default());
+
+    let part_links = parts_for_day(link.day)
+        .into_iter()
+        .map(|part| {
+            let part = part as u32;
+            let href = DayLink::new(link.day).with_part(part).with_args(link.args.clone()).with_step(link.step).href();
+            view! { <A href=href>{format!("Part {part}")}</A> }
+        })
+        .collect_view();
+
+    let link_for_args = link.clone();
+    let apply_args = move |_| {
+        let new_args = Some(args.get_untracked());
+        navigate(&link_for_args.clone().with_args(new_args).href(), Default::default());
+    };
+
+    view! {
+        <div class="flex flex-row gap-4 my-4 items-center">
+            <A href=DayLink::new(link.day).href()>"All testcases"</A>
+            {part_links}
+            <label class="flex flex-row gap-1 items-center">
+                <span>"Custom args"</span>
+                <input
+                    type="text"
+                    class="bg-secondary p-1 font-mono"
+                    prop:value=args
+                    on:input=move |ev| set_args.set(event_target_value(&ev))
+                />
+            </label>
+            {styled_button().on(leptos::ev::click, apply_args).child("Apply")}
+            {link.step.map(|step| view! { <span>{format!("Visualization step: {step}")}</span> })}
+        </div>
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RealTask {
     pub(crate) input: AocDayInput,
@@ -309,7 +404,8 @@ impl TaskStore {
         }
     }
 
-    pub async fn run(&self) {
+    /// Runs every task once - or repeatedly, if a `benchmark_config` is given.
+    pub async fn run(&self, benchmark_config: Option<BenchmarkConfig>) {
         let start_time = Utc::now();
         self.set_status.set(Status::Running { num_tasks_done: 0, start_time });
 
@@ -326,8 +422,6 @@ impl TaskStore {
                             (7, Part2),
                             (9, Part1),
                             (9, Part2),
-                            (12, Part1),
-                            (12, Part2),
                             (22, Part1),
                         ]);
                         match t {
@@ -345,7 +439,7 @@ impl TaskStore {
         let mut num_tasks_done = 0;
 
         while let Some(task) = tasks_queue.pop_front() {
-            let result = run_task(task).await;
+            let result = run_task(task, benchmark_config).await;
             num_tasks_done += 1;
             self.results.get(task).unwrap().1.set(Some(result));
             self.set_status.set(Status::Running { num_tasks_done, start_time });
@@ -358,10 +452,42 @@ impl TaskStore {
             end_time: Utc::now(),
         });
     }
+
+    /// Snapshot of a finished run for the performance history. Returns `None` while the run is still in progress.
+    pub fn to_performance_run(&self) -> Option<PerformanceRun> {
+        let Status::Done { start_time, .. } = self.status.get_untracked() else {
+            return None;
+        };
+
+        let entries = self
+            .result_signals
+            .iter()
+            .filter_map(|(task, signal)| match task {
+                RunTaskData::RunReal {
+                    task: RealTask { input, part },
+                } => signal.get_untracked().map(|solution| PerformanceEntry::new(input.day, part.clone() as u32, &solution)),
+                RunTaskData::RunTestcase { .. } => None,
+            })
+            .sorted_by_key(|e| (e.day, e.part))
+            .collect_vec();
+
+        let user_agent = web_sys::window().and_then(|w| w.navigator().user_agent().ok()).unwrap_or_default();
+
+        Some(PerformanceRun {
+            started_at: start_time,
+            user_agent,
+            build_version: build_version(),
+            entries,
+        })
+    }
 }
 
 #[component]
-fn RunAllComponent(aoc_input_files: Signal<AocInput>) -> impl IntoView {
+fn RunAllComponent(
+    aoc_input_files: Signal<AocInput>,
+    performance_history: Signal<PerformanceHistory>,
+    set_performance_history: WriteSignal<PerformanceHistory>,
+) -> impl IntoView {
     // let testcases_by_day = use_context::<ReadSignal<Vec<(u32, Vec<Testcase>)>>>().expect("to have found the testcases");
     let all_tasks: Vec<RunTaskData> = aoc_input_files
         .get_untracked()
@@ -382,20 +508,24 @@ fn RunAllComponent(aoc_input_files: Signal<AocInput>) -> impl IntoView {
 
     let store: TaskStore = TaskStore::new(all_tasks);
 
-    view! { <RunTasks store=store /> }
+    view! { <RunTasks store=store performance_history=performance_history set_performance_history=set_performance_history /> }
 }
 
-async fn run_task(task: &RunTaskData) -> Solution {
+async fn run_task(task: &RunTaskData, benchmark_config: Option<BenchmarkConfig>) -> Solution {
     log!("running {}", task.id());
+    let solve = |day: u32, part: Part, input: &str, maybe_args: Option<String>| match benchmark_config {
+        None => solve_day(day, part, input, maybe_args),
+        Some(config) => benchmark_day(day, part, input, maybe_args, config),
+    };
     let result = match task {
         RunTaskData::RunReal {
             task: RealTask { input, part },
-        } => solve_day(input.day, part.clone(), &input.input, None),
+        } => solve(input.day, part.clone(), &input.input, None),
         RunTaskData::RunTestcase {
             task: TestcaseTask { testcase, .. },
         } => {
             let part: Part = testcase.part.try_into().unwrap();
-            solve_day(testcase.day, part, &testcase.input, testcase.args.clone())
+            solve(testcase.day, part, &testcase.input, testcase.args.clone())
         }
     };
 
@@ -437,6 +567,12 @@ fn Main() -> impl IntoView {
             <li>
                 <A href="all-days-performance">"performance all days"</A>
             </li>
+            <li>
+                <A href="performance-history">"performance history"</A>
+            </li>
+            <li>
+                <A href="native-vs-wasm">"native vs. wasm"</A>
+            </li>
         </ul>
     };
 
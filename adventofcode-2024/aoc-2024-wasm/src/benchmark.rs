@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// High resolution timestamp in milliseconds.
+/// In the browser `Utc::now()` only has millisecond resolution, `performance.now()` is a lot more precise.
+#[cfg(target_arch = "wasm32")]
+pub fn now_millis() -> f64 {
+    web_sys::window().and_then(|w| w.performance()).map(|p| p.now()).unwrap_or_else(|| chrono::Utc::now().timestamp_micros() as f64 / 1000.0)
+}
+
+/// High resolution timestamp in milliseconds.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn now_millis() -> f64 {
+    use std::sync::OnceLock;
+    use std::time::Instant;
+
+    static START: OnceLock<Instant> = OnceLock::new();
+    START.get_or_init(Instant::now).elapsed().as_secs_f64() * 1000.0
+}
+
+pub fn millis_to_duration(millis: f64) -> Duration {
+    Duration::from_secs_f64(millis.max(0.0) / 1000.0)
+}
+
+/// Runs a solver `max_samples` times - or fewer, if the `time_budget` is used up before.
+/// At least one sample is always taken.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchmarkConfig {
+    pub max_samples: u32,
+    pub time_budget: Duration,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            max_samples: 100,
+            time_budget: Duration::from_secs(1),
+        }
+    }
+}
+
+/// Statistics over all samples - same numbers that divan reports natively.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BenchmarkStats {
+    pub sample_count: usize,
+    pub min: Duration,
+    pub max: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl BenchmarkStats {
+    pub fn from_samples(samples_millis: &[f64]) -> Option<Self> {
+        if samples_millis.is_empty() {
+            return None;
+        }
+
+        let mut sorted = samples_millis.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.0
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        // sample standard deviation (like divan) - zero for a single sample
+        let variance = if n > 1 {
+            sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / (n - 1) as f64
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            sample_count: n,
+            min: millis_to_duration(sorted[0]),
+            max: millis_to_duration(sorted[n - 1]),
+            median: millis_to_duration(median),
+            mean: millis_to_duration(mean),
+            stddev: millis_to_duration(variance.sqrt()),
+        })
+    }
+}
+
+/// Calls `f` repeatedly according to `config` and returns the result of the last call together with the timings.
+pub fn run_benchmark<T>(config: BenchmarkConfig, mut f: impl FnMut() -> T) -> (T, BenchmarkStats) {
+    let budget_millis = config.time_budget.as_secs_f64() * 1000.0;
+    let bench_start = now_millis();
+    let mut samples = vec![];
+
+    loop {
+        let start = now_millis();
+        let result = f();
+        let end = now_millis();
+        samples.push(end - start);
+
+        if samples.len() >= config.max_samples as usize || end - bench_start >= budget_millis {
+            let stats = BenchmarkStats::from_samples(&samples).expect("at least one sample has been taken");
+            return (result, stats);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stats_from_samples() {
+        let stats = BenchmarkStats::from_samples(&[4.0, 1.0, 3.0, 2.0]).unwrap();
+
+        assert_eq!(stats.sample_count, 4);
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.max, Duration::from_millis(4));
+        assert_eq!(stats.median, Duration::from_micros(2500));
+        assert_eq!(stats.mean, Duration::from_micros(2500));
+        // sqrt(5/3) ms
+        assert_eq!(stats.stddev.as_micros(), 1290);
+    }
+
+    #[test]
+    fn test_single_sample_has_no_deviation() {
+        let stats = BenchmarkStats::from_samples(&[0.5]).unwrap();
+        assert_eq!(stats.median, Duration::from_micros(500));
+        assert_eq!(stats.stddev, Duration::ZERO);
+        assert_eq!(BenchmarkStats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn test_run_benchmark_respects_sample_count() {
+        let mut calls = 0;
+        let config = BenchmarkConfig {
+            max_samples: 7,
+            time_budget: Duration::from_secs(60),
+        };
+        let (result, stats) = run_benchmark(config, || {
+            calls += 1;
+            calls
+        });
+
+        assert_eq!(result, 7);
+        assert_eq!(stats.sample_count, 7);
+    }
+
+    #[test]
+    fn test_run_benchmark_stops_after_time_budget() {
+        let config = BenchmarkConfig {
+            max_samples: u32::MAX,
+            time_budget: Duration::ZERO,
+        };
+        let (_, stats) = run_benchmark(config, || ());
+
+        assert_eq!(stats.sample_count, 1);
+    }
+}
@@ -0,0 +1,123 @@
+use std::fmt::Write;
+
+/// State of the day view that is encoded in the url, so that a specific (failing) case can be shared.
+/// `/adventofcode-2024/day/14?part=1&testcase=0&args=11x7&step=100`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DayLink {
+    pub day: u32,
+    pub part: Option<u32>,
+    /// index of the testcase within the selected part
+    pub testcase: Option<usize>,
+    /// custom args that replace the ones from `testcases.toml`
+    pub args: Option<String>,
+    /// step of a visualization
+    pub step: Option<usize>,
+}
+
+impl DayLink {
+    pub const PART: &'static str = "part";
+    pub const TESTCASE: &'static str = "testcase";
+    pub const ARGS: &'static str = "args";
+    pub const STEP: &'static str = "step";
+
+    pub fn new(day: u32) -> Self {
+        Self { day, ..Self::default() }
+    }
+
+    /// Reads the state from the (already decoded) query parameters. Unparsable values are ignored.
+    pub fn from_query(day: u32, get_param: impl Fn(&str) -> Option<String>) -> Self {
+        Self {
+            day,
+            part: get_param(Self::PART).and_then(|p| p.parse().ok()),
+            testcase: get_param(Self::TESTCASE).and_then(|t| t.parse().ok()),
+            args: get_param(Self::ARGS).filter(|a| !a.is_empty()),
+            step: get_param(Self::STEP).and_then(|s| s.parse().ok()),
+        }
+    }
+
+    pub fn with_part(self, part: u32) -> Self {
+        Self { part: Some(part), ..self }
+    }
+
+    pub fn with_testcase(self, testcase: usize) -> Self {
+        Self {
+            testcase: Some(testcase),
+            ..self
+        }
+    }
+
+    pub fn with_args(self, args: Option<String>) -> Self {
+        Self {
+            args: args.filter(|a| !a.is_empty()),
+            ..self
+        }
+    }
+
+    pub fn with_step(self, step: Option<usize>) -> Self {
+        Self { step, ..self }
+    }
+
+    /// Does this link select the given testcase? Links without part/testcase select everything.
+    pub fn selects(&self, part: u32, testcase: usize) -> bool {
+        self.part.is_none_or(|p| p == part) && self.testcase.is_none_or(|t| t == testcase)
+    }
+
+    pub fn query_string(&self) -> String {
+        let params = [
+            (Self::PART, self.part.map(|p| p.to_string())),
+            (Self::TESTCASE, self.testcase.map(|t| t.to_string())),
+            (Self::ARGS, self.args.clone()),
+            (Self::STEP, self.step.map(|s| s.to_string())),
+        ];
+
+        params
+            .into_iter()
+            .filter_map(|(key, maybe_value)| maybe_value.map(|value| format!("{key}={}", encode_query_value(&value))))
+            .reduce(|acc, param| format!("{acc}&{param}"))
+            .map(|query| format!("?{query}"))
+            .unwrap_or_default()
+    }
+
+    /// absolute path within the app, e.g. `/adventofcode-2024/day/14?part=1`
+    pub fn href(&self) -> String {
+        format!("/adventofcode-2024/day/{}{}", self.day, self.query_string())
+    }
+}
+
+/// percent-encodes everything except the unreserved characters of RFC 3986
+fn encode_query_value(value: &str) -> String {
+    value.bytes().fold(String::new(), |mut acc, b| {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => acc.push(b as char),
+            _ => write!(acc, "%{b:02X}").unwrap(),
+        }
+        acc
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_href() {
+        let link = DayLink::new(14).with_part(1).with_testcase(0).with_args(Some("11x7 100".to_string())).with_step(Some(3));
+        assert_eq!(link.href(), "/adventofcode-2024/day/14?part=1&testcase=0&args=11x7%20100&step=3");
+
+        assert_eq!(DayLink::new(1).href(), "/adventofcode-2024/day/1");
+        assert_eq!(DayLink::new(1).with_args(Some(String::new())).href(), "/adventofcode-2024/day/1");
+    }
+
+    #[test]
+    fn test_from_query() {
+        let query = HashMap::from([("part", "2"), ("testcase", "1"), ("args", "6,12"), ("step", "nope")]);
+        let link = DayLink::from_query(18, |key| query.get(key).map(|v| v.to_string()));
+
+        assert_eq!(link, DayLink::new(18).with_part(2).with_testcase(1).with_args(Some("6,12".to_string())));
+        assert!(link.selects(2, 1));
+        assert!(!link.selects(2, 0));
+        assert!(!link.selects(1, 1));
+        assert!(DayLink::new(18).selects(1, 5));
+    }
+}
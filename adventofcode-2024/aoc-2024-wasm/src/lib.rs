@@ -1,14 +1,40 @@
+pub mod benchmark;
+pub mod deep_link;
+pub mod native_benchmarks;
+pub mod performance_history;
 pub mod testcases;
 
+use crate::benchmark::{millis_to_duration, now_millis, run_benchmark, BenchmarkConfig, BenchmarkStats};
 use crate::testcases::{read_all_testcases, Testcase};
 use crate::Part::{Part1, Part2};
-use chrono::{TimeDelta, Utc};
+use chrono::TimeDelta;
 
 #[derive(Clone, Debug)]
 pub struct Solution {
     pub result: String,
     pub error: Option<String>,
+    /// duration of the single run - or the median if the solver has been benchmarked
     pub duration: TimeDelta,
+    pub stats: Option<BenchmarkStats>,
+}
+
+impl Solution {
+    fn new(result: miette::Result<String>, duration: TimeDelta, stats: Option<BenchmarkStats>) -> Self {
+        match result {
+            Ok(result) => Solution {
+                result,
+                error: None,
+                duration,
+                stats,
+            },
+            Err(err) => Solution {
+                result: String::new(),
+                error: Some(err.to_string()),
+                duration,
+                stats,
+            },
+        }
+    }
 }
 
 pub fn init_panic_hook() {
@@ -35,18 +61,20 @@ impl TryFrom<u32> for Part {
 
 pub fn solve_day(day: u32, part: Part, input: &str, maybe_args: Option<String>) -> Solution {
 
-    let start = Utc::now();
+    let start = now_millis();
     let result = solve_day_internal(day, part, input, maybe_args);
-    let end = Utc::now();
-    let duration = end.signed_duration_since(start);
-    match result {
-        Ok(result) => Solution { result, error: None, duration },
-        Err(err) => Solution {
-            result: String::new(),
-            error: Some(err.to_string()),
-            duration,
-        },
-    }
+    let end = now_millis();
+    Solution::new(result, to_time_delta(millis_to_duration(end - start)), None)
+}
+
+/// Runs the solver repeatedly (see [BenchmarkConfig]). The reported duration is the median of all samples.
+pub fn benchmark_day(day: u32, part: Part, input: &str, maybe_args: Option<String>, config: BenchmarkConfig) -> Solution {
+    let (result, stats) = run_benchmark(config, || solve_day_internal(day, part.clone(), input, maybe_args.clone()));
+    Solution::new(result, to_time_delta(stats.median), Some(stats))
+}
+
+fn to_time_delta(duration: std::time::Duration) -> TimeDelta {
+    TimeDelta::from_std(duration).unwrap_or(TimeDelta::MAX)
 }
 
 pub fn get_testcases() -> Vec<Testcase> {
@@ -70,20 +98,38 @@ fn solve_day_internal(day: u32, part: Part, input: &str, maybe_args: Option<Stri
         (05, Part::Part2, _) => day_05::part2::process(input),
         (06, Part::Part1, _) => day_06::part1::process(input),
         (06, Part::Part2, _) => day_06::part2::process(input),
-        (07, Part::Part1, _) => day_07::part1::process(input),
-        (07, Part::Part2, _) => day_07::part2::process(input),
+        (07, Part::Part1, maybe_args) => match maybe_args {
+            None => day_07::part1::process(input),
+            Some(args) => day_07::process_with_args(input, &args),
+        },
+        (07, Part::Part2, maybe_args) => match maybe_args {
+            None => day_07::part2::process(input),
+            Some(args) => day_07::process_with_args(input, &args),
+        },
         (08, Part::Part1, _) => day_08::part1::process(input),
         (08, Part::Part2, _) => day_08::part2::process(input),
         (09, Part::Part1, _) => day_09::part1::process(input),
         (09, Part::Part2, _) => day_09::part2::process(input),
         (10, Part::Part1, _) => day_10::part1::process(input),
         (10, Part::Part2, _) => day_10::part2::process(input),
-        (11, Part::Part1, _) => day_11::part1::process(input),
-        (11, Part::Part2, _) => day_11::part2::process(input),
+        (11, Part::Part1, maybe_args) => match maybe_args {
+            None => day_11::part1::process(input),
+            Some(args) => day_11::process_with_args(input, &args),
+        },
+        (11, Part::Part2, maybe_args) => match maybe_args {
+            None => day_11::part2::process(input),
+            Some(args) => day_11::process_with_args(input, &args),
+        },
         (12, Part::Part1, _) => day_12::part1::process(input),
         (12, Part::Part2, _) => day_12::part2::process(input),
-        (13, Part::Part1, _) => day_13::part1::process(input),
-        (13, Part::Part2, _) => day_13::part2::process(input),
+        (13, Part::Part1, maybe_args) => match maybe_args {
+            None => day_13::part1::process(input),
+            Some(args) => day_13::process_with_args(input, &args),
+        },
+        (13, Part::Part2, maybe_args) => match maybe_args {
+            None => day_13::part2::process(input),
+            Some(args) => day_13::process_with_args(input, &args),
+        },
         (14, Part::Part1, maybe_args) => match maybe_args {
             None => day_14::part1::process(input),
             Some(args) => day_14::part1::process_with_args(input, &args),
@@ -95,7 +141,10 @@ fn solve_day_internal(day: u32, part: Part, input: &str, maybe_args: Option<Stri
         (16, Part::Part2, _) => day_16::part2::process(input),
         (17, Part::Part1, _) => day_17::part1::process(input),
         (17, Part::Part2, _) => day_17::part2::process(input),
-        (18, Part::Part1, _) => day_18::part1::process(input),
+        (18, Part::Part1, maybe_args) => match maybe_args {
+            None => day_18::part1::process(input),
+            Some(args) => day_18::part1::process_with_args(input, &args),
+        },
         (18, Part::Part2, maybe_args) => match maybe_args {
             None => day_18::part2::process(input),
             Some(args) => day_18::part2::process_with_args(input, &args),
@@ -1,5 +1,7 @@
 mod app;
 pub mod components;
+mod native_comparison_component;
+mod performance_history_component;
 mod run_tasks_component;
 
 use app::*;
@@ -0,0 +1,242 @@
+use crate::performance_history::PerformanceRun;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub const NATIVE_BENCHMARKS_STORAGE_KEY: &str = "adventofcode-2024-native-benchmarks";
+
+/// The `benchmarks.txt` that has been checked in with the repo (`just bench-all`).
+pub const BUNDLED_NATIVE_BENCHMARKS: &str = include_str!("../../benchmarks.txt");
+
+/// A day/part is flagged as pathologically slow in WASM if its wasm/native ratio is this much worse than the median ratio of all days.
+pub const SLOW_IN_WASM_FACTOR: f64 = 2.0;
+
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct NativeBenchmarks {
+    pub benchmarks: Vec<NativeBenchmark>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct NativeBenchmark {
+    pub day: u32,
+    pub part: u32,
+    /// name of the bench function, e.g. `part2` or `part2 (rayon)`
+    pub name: String,
+    pub fastest: Duration,
+    pub slowest: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub samples: u32,
+}
+
+impl NativeBenchmarks {
+    /// Accepts either the structured json export (see [NativeBenchmarks]) or the plain divan output.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let trimmed = input.trim();
+        if trimmed.starts_with('{') {
+            serde_json::from_str(trimmed).map_err(|err| format!("Can't parse json export: {err}"))
+        } else {
+            parse_divan_output(trimmed).map(|benchmarks| Self { benchmarks })
+        }
+    }
+
+    /// The benchmark that matches the solver run in the browser.
+    /// That's the one named exactly `part{n}` - or the fastest variant if there is no such bench (e.g. `part2 rewrite`).
+    pub fn for_day_part(&self, day: u32, part: u32) -> Option<&NativeBenchmark> {
+        let candidates = self.benchmarks.iter().filter(|b| b.day == day && b.part == part);
+        let plain_name = format!("part{part}");
+        candidates.clone().find(|b| b.name == plain_name).or_else(|| candidates.min_by_key(|b| b.median))
+    }
+}
+
+/// Parses the table that `cargo bench` prints with divan, e.g.
+/// ```text
+/// day_01_bench        fastest       │ slowest       │ median        │ mean          │ samples │ iters
+/// ├─ part1            44.08 µs      │ 912.8 µs      │ 44.49 µs      │ 53.29 µs      │ 100     │ 100
+/// ╰─ part2            56.49 µs      │ 69.08 µs      │ 59.58 µs      │ 59.86 µs      │ 100     │ 100
+/// ```
+pub fn parse_divan_output(input: &str) -> Result<Vec<NativeBenchmark>, String> {
+    let mut current_day = None;
+    let mut benchmarks = vec![];
+
+    for (line_no, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some(day_str) = line.strip_prefix("day_") {
+            let day = day_str.split('_').next().and_then(|d| d.parse::<u32>().ok());
+            current_day = Some(day.ok_or_else(|| format!("line {}: can't parse day from '{line}'", line_no + 1))?);
+            continue;
+        }
+
+        let Some(row) = line.strip_prefix("├─").or_else(|| line.strip_prefix("╰─")) else {
+            // e.g. the "Timer precision: 41 ns" line divan prints first
+            continue;
+        };
+
+        let day = current_day.ok_or_else(|| format!("line {}: benchmark row without day header", line_no + 1))?;
+        benchmarks.push(parse_divan_row(day, row).map_err(|err| format!("line {}: {err}", line_no + 1))?);
+    }
+
+    if benchmarks.is_empty() {
+        Err("no benchmarks found".to_string())
+    } else {
+        Ok(benchmarks)
+    }
+}
+
+fn parse_divan_row(day: u32, row: &str) -> Result<NativeBenchmark, String> {
+    let columns: Vec<&str> = row.split('│').map(|c| c.trim()).collect();
+    let [name_and_fastest, slowest, median, mean, samples, _iters] = columns.as_slice() else {
+        return Err(format!("expected 6 columns, got {}", columns.len()));
+    };
+
+    // the first column contains the bench name and the fastest time, e.g. "part2 (rayon)    47.54 ms"
+    let mut words: Vec<&str> = name_and_fastest.split_whitespace().collect();
+    if words.len() < 3 {
+        return Err(format!("can't split name and duration in '{name_and_fastest}'"));
+    }
+    let fastest = words.split_off(words.len() - 2).join(" ");
+    let name = words.join(" ");
+
+    let part =
+        name.strip_prefix("part").and_then(|rest| rest.chars().next()).and_then(|c| c.to_digit(10)).ok_or_else(|| format!("can't parse part from '{name}'"))?;
+
+    Ok(NativeBenchmark {
+        day,
+        part,
+        name,
+        fastest: parse_divan_duration(&fastest)?,
+        slowest: parse_divan_duration(slowest)?,
+        median: parse_divan_duration(median)?,
+        mean: parse_divan_duration(mean)?,
+        samples: samples.parse().map_err(|_| format!("can't parse sample count '{samples}'"))?,
+    })
+}
+
+fn parse_divan_duration(s: &str) -> Result<Duration, String> {
+    let (value, unit) = s.split_once(' ').ok_or_else(|| format!("can't parse duration '{s}'"))?;
+    let value: f64 = value.parse().map_err(|_| format!("can't parse duration '{s}'"))?;
+    let secs = match unit {
+        "ns" => value / 1_000_000_000.0,
+        "µs" | "us" => value / 1_000_000.0,
+        "ms" => value / 1_000.0,
+        "s" => value,
+        "m" => value * 60.0,
+        unknown => return Err(format!("unknown time unit '{unknown}'")),
+    };
+    Ok(Duration::from_secs_f64(secs))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct NativeWasmComparison {
+    pub day: u32,
+    pub part: u32,
+    pub native_name: String,
+    pub native: Duration,
+    pub wasm: Duration,
+    /// wasm / native
+    pub ratio: f64,
+    pub slow_in_wasm: bool,
+}
+
+/// Compares the native median with the wasm duration for all day/parts present in both.
+pub fn compare_native_wasm(native: &NativeBenchmarks, wasm_run: &PerformanceRun) -> Vec<NativeWasmComparison> {
+    let mut comparisons: Vec<NativeWasmComparison> = wasm_run
+        .day_parts()
+        .into_iter()
+        .filter_map(|(day, part)| {
+            let native_bench = native.for_day_part(day, part)?;
+            let wasm = wasm_run.entry(day, part)?.duration().to_std().ok()?;
+            let ratio = wasm.as_secs_f64() / native_bench.median.as_secs_f64().max(f64::MIN_POSITIVE);
+            Some(NativeWasmComparison {
+                day,
+                part,
+                native_name: native_bench.name.clone(),
+                native: native_bench.median,
+                wasm,
+                ratio,
+                slow_in_wasm: false,
+            })
+        })
+        .collect();
+
+    let mut ratios: Vec<f64> = comparisons.iter().map(|c| c.ratio).collect();
+    ratios.sort_by(|a, b| a.total_cmp(b));
+    if let Some(median_ratio) = ratios.get(ratios.len() / 2).copied() {
+        comparisons.iter_mut().for_each(|c| c.slow_in_wasm = c.ratio > median_ratio * SLOW_IN_WASM_FACTOR);
+    }
+
+    comparisons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::performance_history::PerformanceEntry;
+    use chrono::DateTime;
+
+    #[test]
+    fn test_parse_bundled_benchmarks() -> Result<(), String> {
+        let native = NativeBenchmarks::parse(BUNDLED_NATIVE_BENCHMARKS)?;
+
+        // 24 days with two parts, day 25 has only one. Days 22 and 24 have extra variants
+        assert_eq!(native.benchmarks.len(), 24 * 2 + 1 + 1 + 2);
+
+        let day_01_part_1 = native.for_day_part(1, 1).unwrap();
+        assert_eq!(day_01_part_1.median, Duration::from_nanos(44_490));
+        assert_eq!(day_01_part_1.slowest, Duration::from_nanos(912_800));
+        assert_eq!(day_01_part_1.samples, 100);
+
+        assert_eq!(native.for_day_part(17, 1).unwrap().median, Duration::from_nanos(957));
+        assert_eq!(native.for_day_part(22, 2).unwrap().name, "part2");
+        // no plain "part2" bench for day 24 - fastest variant wins
+        assert_eq!(native.for_day_part(24, 2).unwrap().name, "part2 rewrite");
+        assert_eq!(native.benchmarks.iter().find(|b| b.name == "part2 (lol)").unwrap().slowest.as_secs(), 62);
+        Ok(())
+    }
+
+    #[test]
+    fn test_json_export_round_trip() -> Result<(), String> {
+        let native = NativeBenchmarks::parse(BUNDLED_NATIVE_BENCHMARKS)?;
+        let json = serde_json::to_string(&native).unwrap();
+        assert_eq!(NativeBenchmarks::parse(&json)?, native);
+        Ok(())
+    }
+
+    #[test]
+    fn test_compare_native_wasm() -> Result<(), String> {
+        let native = NativeBenchmarks::parse(
+            r#"
+day_01_bench        fastest       │ slowest       │ median        │ mean          │ samples │ iters
+├─ part1            10 µs         │ 10 µs         │ 10 µs         │ 10 µs         │ 100     │ 100
+╰─ part2            10 µs         │ 10 µs         │ 10 µs         │ 10 µs         │ 100     │ 100
+
+day_02_bench        fastest       │ slowest       │ median        │ mean          │ samples │ iters
+├─ part1            10 µs         │ 10 µs         │ 10 µs         │ 10 µs         │ 100     │ 100
+"#,
+        )?;
+        let wasm_run = PerformanceRun {
+            started_at: DateTime::from_timestamp_millis(0).unwrap(),
+            user_agent: "test".to_string(),
+            build_version: "test".to_string(),
+            entries: [(1, 1, 20), (1, 2, 25), (2, 1, 200), (3, 1, 10)]
+                .into_iter()
+                .map(|(day, part, duration_micros)| PerformanceEntry {
+                    day,
+                    part,
+                    duration_micros,
+                    result: String::new(),
+                    error: None,
+                    stats: None,
+                })
+                .collect(),
+        };
+
+        let actual = compare_native_wasm(&native, &wasm_run).into_iter().map(|c| (c.day, c.part, c.ratio, c.slow_in_wasm)).collect::<Vec<_>>();
+
+        assert_eq!(actual, vec![(1, 1, 2.0, false), (1, 2, 2.5, false), (2, 1, 20.0, true)]);
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use crate::components::{read_file_content, styled_button};
+use crate::performance_history_component::RunSelect;
+use aoc_2024_wasm::native_benchmarks::{
+    compare_native_wasm, NativeBenchmarks, NativeWasmComparison, BUNDLED_NATIVE_BENCHMARKS, NATIVE_BENCHMARKS_STORAGE_KEY, SLOW_IN_WASM_FACTOR,
+};
+use aoc_2024_wasm::performance_history::PerformanceHistory;
+use codee::string::JsonSerdeCodec;
+use humantime::format_duration;
+use leptos::ev::click;
+use leptos::prelude::*;
+use leptos::task::spawn_local;
+use leptos::{component, IntoView};
+use leptos_use::storage::use_local_storage;
+use send_wrapper::SendWrapper;
+use web_sys::HtmlInputElement;
+
+#[component]
+pub fn NativeComparison(performance_history: Signal<PerformanceHistory>) -> impl IntoView {
+    let (native_benchmarks, set_native_benchmarks, _) = use_local_storage::<NativeBenchmarks, JsonSerdeCodec>(NATIVE_BENCHMARKS_STORAGE_KEY);
+    let (parse_error, set_parse_error) = signal(None::<String>);
+    let (pasted, set_pasted) = signal(String::new());
+    let (wasm_run_id, set_wasm_run_id) = signal(None::<i64>);
+
+    let load = move |content: String| match NativeBenchmarks::parse(&content) {
+        Ok(parsed) => {
+            set_parse_error.set(None);
+            set_native_benchmarks.set(parsed);
+        }
+        Err(err) => set_parse_error.set(Some(err)),
+    };
+
+    let wasm_run = Memo::new(move |_| {
+        let history = performance_history.read();
+        wasm_run_id.get().and_then(|id| history.runs.iter().find(|r| r.id() == id)).or(history.latest()).cloned()
+    });
+
+    let comparisons = Memo::new(move |_| wasm_run.get().map(|run| compare_native_wasm(&native_benchmarks.read(), &run)).unwrap_or_default());
+
+    view! {
+        <div class="p-4 flex flex-col gap-4">
+            <h1 class="text-2xl font-bold">"Native vs. WASM"</h1>
+            <p>
+                "Load native numbers (the output of "<span class="font-mono bg-secondary">"just bench-all"</span>
+                " or a json export of this page) and compare them with a stored browser run."
+            </p>
+            <div class="flex flex-row gap-4 items-center">
+                {styled_button().on(click, move |_| load(BUNDLED_NATIVE_BENCHMARKS.to_string())).child("Use bundled benchmarks.txt")}
+                <input
+                    type="file"
+                    on:change=move |ev| {
+                        let input: HtmlInputElement = event_target(&ev);
+                        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                            let file = SendWrapper::new(file);
+                            spawn_local(async move { load(read_file_content(&file).await) });
+                        }
+                    }
+                />
+            </div>
+            <textarea
+                class="font-mono whitespace-pre bg-secondary h-36"
+                placeholder="...or paste divan output here"
+                prop:value=pasted
+                on:input=move |ev| set_pasted.set(event_target_value(&ev))
+            />
+            <div class="flex flex-row gap-4">
+                {styled_button().on(click, move |_| load(pasted.get())).child("Parse pasted benchmarks")}
+                {styled_button()
+                    .on(
+                        click,
+                        move |_| {
+                            set_pasted.set(serde_json::to_string_pretty(&native_benchmarks.get()).unwrap_or_default())
+                        },
+                    )
+                    .child("Export as json")}
+            </div>
+            {move || parse_error.get().map(|err| view! { <p class="text-red-500">{err}</p> })}
+            <p>{move || format!("{} native benchmark(s) loaded", native_benchmarks.read().benchmarks.len())}</p>
+            <RunSelect label="WASM run" performance_history=performance_history selected=wasm_run set_selected=set_wasm_run_id />
+            <p>
+                {format!(
+                    "Rows in red are more than {SLOW_IN_WASM_FACTOR}x slower (relative to native) than the median day.",
+                )}
+            </p>
+            <ComparisonTable comparisons=comparisons />
+        </div>
+    }
+}
+
+#[component]
+fn ComparisonTable(comparisons: Memo<Vec<NativeWasmComparison>>) -> impl IntoView {
+    view! {
+        <table class="table-auto border border-collapse border-gray-400 dark:border-gray-500">
+            <thead>
+                <tr>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2">"Day"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2">"Part"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2">"Native Bench"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2 text-right">"Native (median)"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2 text-right">"WASM"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2 text-right">"WASM / Native"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {move || {
+                    comparisons
+                        .get()
+                        .into_iter()
+                        .map(|c| {
+                            let row_class = if c.slow_in_wasm { "text-red-500" } else { "" };
+                            view! {
+                                <tr class=row_class>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2">{format!("{:02}", c.day)}</td>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2">{c.part}</td>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2 font-mono">{c.native_name}</td>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2 text-right">{format_duration(c.native).to_string()}</td>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2 text-right">{format_duration(c.wasm).to_string()}</td>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2 text-right">{format!("x{:.2}", c.ratio)}</td>
+                                </tr>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </tbody>
+        </table>
+    }
+}
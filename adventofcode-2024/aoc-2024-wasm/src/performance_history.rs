@@ -0,0 +1,198 @@
+use crate::benchmark::BenchmarkStats;
+use crate::Solution;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const PERFORMANCE_HISTORY_STORAGE_KEY: &str = "adventofcode-2024-performance-history";
+
+/// A day/part counts as regressed (or improved) if it got slower (or faster) by more than this factor.
+pub const DEFAULT_REGRESSION_THRESHOLD: f64 = 1.2;
+
+/// Version of the frontend build.
+/// Set `AOC_BUILD_VERSION` at build time (e.g. to the git hash) to tell builds apart - falls back to the crate version.
+pub fn build_version() -> String {
+    option_env!("AOC_BUILD_VERSION").unwrap_or(env!("CARGO_PKG_VERSION")).to_string()
+}
+
+#[derive(Default, Debug, Deserialize, Serialize, PartialEq, Clone)]
+pub struct PerformanceHistory {
+    pub runs: Vec<PerformanceRun>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct PerformanceRun {
+    pub started_at: DateTime<Utc>,
+    pub user_agent: String,
+    pub build_version: String,
+    pub entries: Vec<PerformanceEntry>,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Clone)]
+pub struct PerformanceEntry {
+    pub day: u32,
+    pub part: u32,
+    pub duration_micros: i64,
+    pub result: String,
+    pub error: Option<String>,
+    /// only present if the run was done in benchmark mode - `duration_micros` is the median then
+    #[serde(default)]
+    pub stats: Option<BenchmarkStats>,
+}
+
+impl PerformanceEntry {
+    pub fn new(day: u32, part: u32, solution: &Solution) -> Self {
+        Self {
+            day,
+            part,
+            duration_micros: solution.duration.num_microseconds().unwrap_or(i64::MAX),
+            result: solution.result.clone(),
+            error: solution.error.clone(),
+            stats: solution.stats.clone(),
+        }
+    }
+
+    pub fn duration(&self) -> TimeDelta {
+        TimeDelta::microseconds(self.duration_micros)
+    }
+}
+
+impl PerformanceRun {
+    pub fn id(&self) -> i64 {
+        self.started_at.timestamp_millis()
+    }
+
+    pub fn total_duration(&self) -> TimeDelta {
+        TimeDelta::microseconds(self.entries.iter().map(|e| e.duration_micros).sum())
+    }
+
+    pub fn entry(&self, day: u32, part: u32) -> Option<&PerformanceEntry> {
+        self.entries.iter().find(|e| e.day == day && e.part == part)
+    }
+
+    /// all (day, part) combinations of this run in ascending order
+    pub fn day_parts(&self) -> Vec<(u32, u32)> {
+        let mut day_parts: Vec<(u32, u32)> = self.entries.iter().map(|e| (e.day, e.part)).collect();
+        day_parts.sort();
+        day_parts.dedup();
+        day_parts
+    }
+}
+
+impl PerformanceHistory {
+    pub fn add_run(&mut self, run: PerformanceRun) {
+        self.runs.push(run);
+        self.runs.sort_by_key(|r| r.started_at);
+    }
+
+    pub fn remove_run(&mut self, id: i64) {
+        self.runs.retain(|r| r.id() != id);
+    }
+
+    pub fn latest(&self) -> Option<&PerformanceRun> {
+        self.runs.last()
+    }
+
+    pub fn previous(&self, id: i64) -> Option<&PerformanceRun> {
+        let idx = self.runs.iter().position(|r| r.id() == id)?;
+        idx.checked_sub(1).and_then(|prev_idx| self.runs.get(prev_idx))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trend {
+    Regression,
+    Improvement,
+    Unchanged,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunComparison {
+    pub day: u32,
+    pub part: u32,
+    pub baseline: TimeDelta,
+    pub current: TimeDelta,
+    /// current / baseline - values above 1 mean the current run was slower
+    pub ratio: f64,
+    pub trend: Trend,
+}
+
+/// Compares all day/parts that are present in both runs.
+pub fn compare_runs(baseline: &PerformanceRun, current: &PerformanceRun, threshold: f64) -> Vec<RunComparison> {
+    current
+        .day_parts()
+        .into_iter()
+        .filter_map(|(day, part)| {
+            let baseline_entry = baseline.entry(day, part)?;
+            let current_entry = current.entry(day, part)?;
+
+            // avoid division by zero for days that finish within the clock resolution
+            let ratio = current_entry.duration_micros.max(1) as f64 / baseline_entry.duration_micros.max(1) as f64;
+            let trend = if ratio > threshold {
+                Trend::Regression
+            } else if ratio < 1.0 / threshold {
+                Trend::Improvement
+            } else {
+                Trend::Unchanged
+            };
+
+            Some(RunComparison {
+                day,
+                part,
+                baseline: baseline_entry.duration(),
+                current: current_entry.duration(),
+                ratio,
+                trend,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(started_at_millis: i64, durations: &[(u32, u32, i64)]) -> PerformanceRun {
+        PerformanceRun {
+            started_at: DateTime::from_timestamp_millis(started_at_millis).unwrap(),
+            user_agent: "test".to_string(),
+            build_version: build_version(),
+            entries: durations
+                .iter()
+                .map(|&(day, part, duration_micros)| PerformanceEntry {
+                    day,
+                    part,
+                    duration_micros,
+                    result: String::new(),
+                    error: None,
+                    stats: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_compare_runs() {
+        let baseline = run(1, &[(1, 1, 100), (1, 2, 1000), (2, 1, 50), (3, 1, 10)]);
+        let current = run(2, &[(1, 1, 130), (1, 2, 500), (2, 1, 55), (4, 1, 10)]);
+
+        let trends = compare_runs(&baseline, &current, DEFAULT_REGRESSION_THRESHOLD).into_iter().map(|c| (c.day, c.part, c.trend)).collect::<Vec<_>>();
+
+        assert_eq!(trends, vec![(1, 1, Trend::Regression), (1, 2, Trend::Improvement), (2, 1, Trend::Unchanged)]);
+    }
+
+    #[test]
+    fn test_history_keeps_runs_ordered() {
+        let mut history = PerformanceHistory::default();
+        history.add_run(run(20, &[(1, 1, 100)]));
+        history.add_run(run(10, &[(1, 1, 200)]));
+        history.add_run(run(30, &[(1, 1, 300)]));
+
+        assert_eq!(history.latest().map(|r| r.id()), Some(30));
+        assert_eq!(history.previous(20).map(|r| r.id()), Some(10));
+        assert_eq!(history.previous(10), None);
+
+        history.remove_run(30);
+        assert_eq!(history.latest().map(|r| r.id()), Some(20));
+        assert_eq!(history.latest().unwrap().total_duration(), TimeDelta::microseconds(100));
+    }
+}
@@ -0,0 +1,287 @@
+use crate::components::styled_button;
+use aoc_2024_wasm::performance_history::{compare_runs, PerformanceHistory, PerformanceRun, RunComparison, Trend, DEFAULT_REGRESSION_THRESHOLD};
+use chrono::TimeDelta;
+use humantime::format_duration;
+use itertools::Itertools;
+use leptos::ev::click;
+use leptos::prelude::*;
+use leptos::{component, IntoView};
+
+const CHART_WIDTH: f64 = 600.0;
+const LABEL_WIDTH: f64 = 60.0;
+const BAR_HEIGHT: f64 = 8.0;
+const ROW_GAP: f64 = 6.0;
+
+#[component]
+pub fn PerformanceHistoryView(performance_history: Signal<PerformanceHistory>, set_performance_history: WriteSignal<PerformanceHistory>) -> impl IntoView {
+    let (baseline_id, set_baseline_id) = signal(None::<i64>);
+    let (current_id, set_current_id) = signal(None::<i64>);
+
+    // defaults to comparing the latest run with the one before
+    let current_run = Memo::new(move |_| {
+        let history = performance_history.read();
+        current_id.get().and_then(|id| history.runs.iter().find(|r| r.id() == id)).or(history.latest()).cloned()
+    });
+    let baseline_run = Memo::new(move |_| {
+        let history = performance_history.read();
+        let current = current_run.get()?;
+        baseline_id.get().and_then(|id| history.runs.iter().find(|r| r.id() == id)).or(history.previous(current.id())).cloned()
+    });
+
+    let comparisons = Memo::new(move |_| match (baseline_run.get(), current_run.get()) {
+        (Some(baseline), Some(current)) => compare_runs(&baseline, &current, DEFAULT_REGRESSION_THRESHOLD),
+        _ => vec![],
+    });
+
+    view! {
+        <div class="p-4 flex flex-col gap-4">
+            <h1 class="text-2xl font-bold">"Performance History"</h1>
+            <p>
+                {move || format!("{} stored run(s). ", performance_history.read().runs.len())}
+                "Runs are stored in localstorage after each complete run on the 'performance all days' page."
+            </p>
+            <RunTable performance_history=performance_history set_performance_history=set_performance_history />
+            <h2 class="text-xl font-bold">"Total Duration Per Run"</h2>
+            <TotalDurationChart performance_history=performance_history />
+            <h2 class="text-xl font-bold">"Compare Runs"</h2>
+            <div class="flex flex-row gap-4">
+                <RunSelect label="Baseline" performance_history=performance_history selected=baseline_run set_selected=set_baseline_id />
+                <RunSelect label="Current" performance_history=performance_history selected=current_run set_selected=set_current_id />
+            </div>
+            <p>
+                {move || {
+                    let (regressions, improvements) = comparisons
+                        .read()
+                        .iter()
+                        .fold((0, 0), |(r, i), c| match c.trend {
+                            Trend::Regression => (r + 1, i),
+                            Trend::Improvement => (r, i + 1),
+                            Trend::Unchanged => (r, i),
+                        });
+                    format!(
+                        "{regressions} regression(s), {improvements} improvement(s) (threshold: {:.0}%)",
+                        (DEFAULT_REGRESSION_THRESHOLD - 1.0) * 100.0,
+                    )
+                }}
+            </p>
+            <ComparisonChart comparisons=comparisons />
+            <ComparisonTable comparisons=comparisons />
+        </div>
+    }
+}
+
+#[component]
+fn RunTable(performance_history: Signal<PerformanceHistory>, set_performance_history: WriteSignal<PerformanceHistory>) -> impl IntoView {
+    view! {
+        <table class="table-auto border border-collapse border-gray-400 dark:border-gray-500">
+            <thead>
+                <tr>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2">"Started"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2">"Build"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2">"User Agent"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2 text-right">"Tasks"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2 text-right">"Total Time"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2"></th>
+                </tr>
+            </thead>
+            <tbody>
+                <For
+                    each=move || performance_history.get().runs.into_iter().rev()
+                    key=|run| run.id()
+                    children=move |run: PerformanceRun| {
+                        let id = run.id();
+                        view! {
+                            <tr>
+                                <td class="border border-gray-300 dark:border-gray-700 p-2">
+                                    {run.started_at.format("%Y-%m-%d %H:%M:%S").to_string()}
+                                </td>
+                                <td class="border border-gray-300 dark:border-gray-700 p-2 font-mono">{run.build_version.clone()}</td>
+                                <td class="border border-gray-300 dark:border-gray-700 p-2 text-xs">{run.user_agent.clone()}</td>
+                                <td class="border border-gray-300 dark:border-gray-700 p-2 text-right">{run.entries.len()}</td>
+                                <td class="border border-gray-300 dark:border-gray-700 p-2 text-right">{pretty_print_duration(run.total_duration())}</td>
+                                <td class="border border-gray-300 dark:border-gray-700 p-2">
+                                    {styled_button()
+                                        .on(click, move |_| set_performance_history.update(|history| history.remove_run(id)))
+                                        .child("Delete")}
+                                </td>
+                            </tr>
+                        }
+                    }
+                />
+            </tbody>
+        </table>
+    }
+}
+
+#[component]
+pub(crate) fn RunSelect(
+    label: &'static str,
+    performance_history: Signal<PerformanceHistory>,
+    selected: Memo<Option<PerformanceRun>>,
+    set_selected: WriteSignal<Option<i64>>,
+) -> impl IntoView {
+    view! {
+        <label class="flex flex-col gap-1">
+            <span class="font-bold">{label}</span>
+            <select
+                class="bg-secondary p-1"
+                on:change=move |ev| set_selected.set(event_target_value(&ev).parse().ok())
+                prop:value=move || selected.get().map(|r| r.id().to_string()).unwrap_or_default()
+            >
+                {move || {
+                    performance_history
+                        .get()
+                        .runs
+                        .iter()
+                        .rev()
+                        .map(|run| {
+                            view! {
+                                <option value=run.id().to_string()>
+                                    {format!(
+                                        "{} - {} ({})",
+                                        run.started_at.format("%Y-%m-%d %H:%M:%S"),
+                                        pretty_print_duration(run.total_duration()),
+                                        run.build_version,
+                                    )}
+                                </option>
+                            }
+                        })
+                        .collect_view()
+                }}
+            </select>
+        </label>
+    }
+}
+
+#[component]
+fn TotalDurationChart(performance_history: Signal<PerformanceHistory>) -> impl IntoView {
+    move || {
+        let history = performance_history.get();
+        let max_micros = history.runs.iter().map(|r| r.total_duration().num_microseconds().unwrap_or(0)).max().unwrap_or(1).max(1);
+        let height = history.runs.len() as f64 * (BAR_HEIGHT * 2.0 + ROW_GAP) + ROW_GAP;
+
+        let bars = history
+            .runs
+            .iter()
+            .enumerate()
+            .map(|(idx, run)| {
+                let y = ROW_GAP + idx as f64 * (BAR_HEIGHT * 2.0 + ROW_GAP);
+                let micros = run.total_duration().num_microseconds().unwrap_or(0);
+                let width = micros as f64 / max_micros as f64 * (CHART_WIDTH - LABEL_WIDTH * 3.0);
+                view! {
+                    <text x="0" y=y + BAR_HEIGHT * 1.5 class="fill-current text-xs">
+                        {run.started_at.format("%m-%d %H:%M").to_string()}
+                    </text>
+                    <rect x=LABEL_WIDTH * 1.5 y=y width=width height=BAR_HEIGHT * 2.0 class="fill-sky-600" />
+                    <text x=LABEL_WIDTH * 1.5 + width + 4.0 y=y + BAR_HEIGHT * 1.5 class="fill-current text-xs">
+                        {pretty_print_duration(run.total_duration())}
+                    </text>
+                }
+            })
+            .collect_view();
+
+        view! {
+            <svg width=CHART_WIDTH height=height viewBox=format!("0 0 {CHART_WIDTH} {height}")>
+                {bars}
+            </svg>
+        }
+    }
+}
+
+/// Grouped bars per day/part: baseline on top, current run below - colored by trend.
+/// Uses a log scale, because durations range from microseconds to seconds.
+#[component]
+fn ComparisonChart(comparisons: Memo<Vec<RunComparison>>) -> impl IntoView {
+    move || {
+        let comparisons = comparisons.get();
+        if comparisons.is_empty() {
+            return view! { <p>"Need at least two runs with overlapping days to compare."</p> }.into_any();
+        }
+
+        let max_micros = comparisons.iter().flat_map(|c| [c.baseline, c.current]).map(|d| d.num_microseconds().unwrap_or(0)).max().unwrap_or(1);
+        let scale = |d: TimeDelta| log_scale(d.num_microseconds().unwrap_or(0), max_micros) * (CHART_WIDTH - LABEL_WIDTH * 2.0);
+        let row_height = BAR_HEIGHT * 2.0 + ROW_GAP;
+        let height = comparisons.len() as f64 * row_height + ROW_GAP;
+
+        let rows = comparisons
+            .iter()
+            .enumerate()
+            .map(|(idx, c)| {
+                let y = ROW_GAP + idx as f64 * row_height;
+                let current_class = match c.trend {
+                    Trend::Regression => "fill-red-500",
+                    Trend::Improvement => "fill-green-500",
+                    Trend::Unchanged => "fill-sky-600",
+                };
+                view! {
+                    <text x="0" y=y + BAR_HEIGHT * 1.5 class="fill-current text-xs">
+                        {format!("{:02} / {}", c.day, c.part)}
+                    </text>
+                    <rect x=LABEL_WIDTH y=y width=scale(c.baseline) height=BAR_HEIGHT class="fill-gray-500" />
+                    <rect x=LABEL_WIDTH y=y + BAR_HEIGHT width=scale(c.current) height=BAR_HEIGHT class=current_class />
+                    <text x=LABEL_WIDTH + scale(c.baseline.max(c.current)) + 4.0 y=y + BAR_HEIGHT * 1.5 class="fill-current text-xs">
+                        {format!("x{:.2}", c.ratio)}
+                    </text>
+                }
+            })
+            .collect_view();
+
+        view! {
+            <svg width=CHART_WIDTH height=height viewBox=format!("0 0 {CHART_WIDTH} {height}")>
+                {rows}
+            </svg>
+        }
+        .into_any()
+    }
+}
+
+#[component]
+fn ComparisonTable(comparisons: Memo<Vec<RunComparison>>) -> impl IntoView {
+    view! {
+        <table class="table-auto border border-collapse border-gray-400 dark:border-gray-500">
+            <thead>
+                <tr>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2">"Day"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2">"Part"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2 text-right">"Baseline"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2 text-right">"Current"</th>
+                    <th class="border border-gray-300 dark:border-gray-600 p-2 text-right">"Ratio"</th>
+                </tr>
+            </thead>
+            <tbody>
+                {move || {
+                    comparisons
+                        .get()
+                        .into_iter()
+                        .map(|c| {
+                            let ratio_class = match c.trend {
+                                Trend::Regression => "border border-gray-300 dark:border-gray-700 p-2 text-right text-red-500",
+                                Trend::Improvement => "border border-gray-300 dark:border-gray-700 p-2 text-right text-green-500",
+                                Trend::Unchanged => "border border-gray-300 dark:border-gray-700 p-2 text-right",
+                            };
+                            view! {
+                                <tr>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2">{format!("{:02}", c.day)}</td>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2">{c.part}</td>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2 text-right">{pretty_print_duration(c.baseline)}</td>
+                                    <td class="border border-gray-300 dark:border-gray-700 p-2 text-right">{pretty_print_duration(c.current)}</td>
+                                    <td class=ratio_class>{format!("x{:.2}", c.ratio)}</td>
+                                </tr>
+                            }
+                        })
+                        .collect_vec()
+                }}
+            </tbody>
+        </table>
+    }
+}
+
+/// maps 1µs..max onto 0..1 logarithmically
+fn log_scale(micros: i64, max_micros: i64) -> f64 {
+    let max = (max_micros.max(2) as f64).log10();
+    ((micros.max(1) as f64).log10() / max).clamp(0.0, 1.0)
+}
+
+fn pretty_print_duration(duration: TimeDelta) -> String {
+    format_duration(duration.to_std().unwrap_or_default()).to_string()
+}
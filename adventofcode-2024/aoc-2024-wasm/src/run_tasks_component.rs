@@ -1,5 +1,7 @@
 use crate::app::{RunTaskData, Status, TaskStore};
 use crate::components::{styled_button, AocDayInput};
+use aoc_2024_wasm::benchmark::{BenchmarkConfig, BenchmarkStats};
+use aoc_2024_wasm::performance_history::PerformanceHistory;
 use aoc_2024_wasm::{Part, Solution};
 use chrono::{DateTime, Utc};
 use humantime::format_duration;
@@ -9,15 +11,27 @@ use leptos::logging::log;
 use leptos::prelude::*;
 use leptos::{component, IntoView};
 use leptos_router::components::A;
+use std::time::Duration;
 
 #[component]
-pub fn RunTasks(store: TaskStore) -> impl IntoView {
+pub fn RunTasks(store: TaskStore, performance_history: Signal<PerformanceHistory>, set_performance_history: WriteSignal<PerformanceHistory>) -> impl IntoView {
     let store_clone = store.clone();
     let num_tasks = store.result_signals.len();
 
-    let run_tasks = Action::new_local(move |_: &()| {
+    let default_benchmark_config = BenchmarkConfig::default();
+    let (benchmark_mode, set_benchmark_mode) = signal(false);
+    let (max_samples, set_max_samples) = signal(default_benchmark_config.max_samples);
+    let (time_budget_ms, set_time_budget_ms) = signal(default_benchmark_config.time_budget.as_millis() as u64);
+
+    let run_tasks = Action::new_local(move |benchmark_config: &Option<BenchmarkConfig>| {
         let sure_why_not_clone_again = store_clone.clone();
-        async move { sure_why_not_clone_again.run().await }
+        let benchmark_config = *benchmark_config;
+        async move {
+            sure_why_not_clone_again.run(benchmark_config).await;
+            if let Some(performance_run) = sure_why_not_clone_again.to_performance_run() {
+                set_performance_history.update(|history| history.add_run(performance_run));
+            }
+        }
     });
 
     if store.result_signals.is_empty() {
@@ -53,11 +67,55 @@ pub fn RunTasks(store: TaskStore) -> impl IntoView {
                         .on(
                             click,
                             move |_| {
-                                run_tasks.dispatch(());
+                                let benchmark_config = benchmark_mode
+                                    .get()
+                                    .then(|| BenchmarkConfig {
+                                        max_samples: max_samples.get().max(1),
+                                        time_budget: Duration::from_millis(time_budget_ms.get()),
+                                    });
+                                run_tasks.dispatch(benchmark_config);
                             },
                         )
                         .child("Run All Tasks")
                 }}
+                <div class="flex flex-row gap-4 my-2 items-center">
+                    <label class="flex flex-row gap-1 items-center">
+                        <input
+                            type="checkbox"
+                            prop:checked=benchmark_mode
+                            on:change=move |ev| set_benchmark_mode.set(event_target_checked(&ev))
+                        />
+                        <span>"Benchmark mode"</span>
+                    </label>
+                    <label class="flex flex-row gap-1 items-center">
+                        <span>"max. samples"</span>
+                        <input
+                            type="number"
+                            min="1"
+                            class="bg-secondary w-20 p-1"
+                            prop:value=move || max_samples.get().to_string()
+                            on:change=move |ev| {
+                                if let Ok(samples) = event_target_value(&ev).parse() {
+                                    set_max_samples.set(samples)
+                                }
+                            }
+                        />
+                    </label>
+                    <label class="flex flex-row gap-1 items-center">
+                        <span>"time budget per task (ms)"</span>
+                        <input
+                            type="number"
+                            min="0"
+                            class="bg-secondary w-24 p-1"
+                            prop:value=move || time_budget_ms.get().to_string()
+                            on:change=move |ev| {
+                                if let Ok(budget) = event_target_value(&ev).parse() {
+                                    set_time_budget_ms.set(budget)
+                                }
+                            }
+                        />
+                    </label>
+                </div>
                 // Combined Tasks and Results view
                 <div class="mb-4">
                     <h2 class="text-xl mb-2">"Tasks:"</h2>
@@ -85,7 +143,27 @@ pub fn RunTasks(store: TaskStore) -> impl IntoView {
                             }
                         }}
                     </div>
-                    <p>"For reference: Took ~9.5s on macbook pro m1 max 64GB."</p>
+                    <p>
+                        {move || {
+                            performance_history
+                                .read()
+                                .latest()
+                                .map(|latest| {
+                                    format!(
+                                        "Last stored run: {} on {} (build {}). ",
+                                        format_duration(latest.total_duration().to_std().unwrap()),
+                                        latest.started_at.format("%Y-%m-%d %H:%M"),
+                                        latest.build_version,
+                                    )
+                                })
+                                .unwrap_or_else(|| "No stored runs yet. ".to_string())
+                        }}
+                        <A href="/adventofcode-2024/performance-history">
+                            <span class="font-medium text-blue-800 underline dark:text-blue-300 hover:no-underline">
+                                "Compare runs here"
+                            </span>
+                        </A>
+                    </p>
                     <div class="space-y-2">
                         <table class="table-auto border border-collapse border-gray-400 dark:border-gray-500">
                             <thead>
@@ -187,13 +265,30 @@ fn ResultRowRealTask(input: AocDayInput, #[prop(into)] part_result_signals: Vec<
                                     .into_any()
                             }
                             Some(result) => {
+                                let stats_title = result.stats.as_ref().map(stats_details);
                                 (view! {
                                     <td class="border border-gray-300 dark:border-gray-700 text-right p-2">
                                         {result.result}
                                     </td>
-                                    <td class="border border-gray-300 dark:border-gray-700 text-right p-2">
+                                    <td
+                                        class="border border-gray-300 dark:border-gray-700 text-right p-2"
+                                        title=stats_title
+                                    >
                                         {format_duration(result.duration.to_std().unwrap())
                                             .to_string()}
+                                        {result
+                                            .stats
+                                            .map(|stats| {
+                                                view! {
+                                                    <span class="block text-xs">
+                                                        {format!(
+                                                            "± {} (n={})",
+                                                            format_duration(stats.stddev),
+                                                            stats.sample_count,
+                                                        )}
+                                                    </span>
+                                                }
+                                            })}
                                     </td>
                                 })
                                     .into_any()
@@ -206,6 +301,18 @@ fn ResultRowRealTask(input: AocDayInput, #[prop(into)] part_result_signals: Vec<
     }
 }
 
+fn stats_details(stats: &BenchmarkStats) -> String {
+    format!(
+        "fastest: {}\nslowest: {}\nmedian: {}\nmean: {}\nstddev: {}\nsamples: {}",
+        format_duration(stats.min),
+        format_duration(stats.max),
+        format_duration(stats.median),
+        format_duration(stats.mean),
+        format_duration(stats.stddev),
+        stats.sample_count,
+    )
+}
+
 fn pretty_print_time_delta(start: DateTime<Utc>, end: DateTime<Utc>) -> String {
     let duration = end - start;
     let std_duration = duration.to_std().unwrap();
@@ -0,0 +1,44 @@
+//! Runs every entry of `testcases.toml` through `solve_day`.
+//!
+//! Compiled to wasm this checks the solvers the way the web app runs them (u64/f64 arithmetic, getrandom with the `js` feature, rayon without threads, ...):
+//! ```shell
+//! cargo install wasm-bindgen-cli --version 0.2.99 # must match the wasm-bindgen version in Cargo.lock
+//! just test-wasm
+//! ```
+//! The same tests also run natively with `cargo test -p aoc-2024-wasm`.
+
+use aoc_2024_wasm::testcases::Testcase;
+use aoc_2024_wasm::{get_testcases, solve_day, Part};
+#[cfg(target_arch = "wasm32")]
+use wasm_bindgen_test::wasm_bindgen_test;
+
+fn run_testcase(testcase: &Testcase) -> Result<(), String> {
+    let part = Part::try_from(testcase.part)?;
+    let solution = solve_day(testcase.day, part, &testcase.input, testcase.args.clone());
+
+    match solution.error {
+        Some(err) => Err(format!("Day {:02} Part {}: failed with '{err}'", testcase.day, testcase.part)),
+        None if solution.result != testcase.solution => Err(format!(
+            "Day {:02} Part {}: expected '{}', got '{}'",
+            testcase.day, testcase.part, testcase.solution, solution.result
+        )),
+        None => Ok(()),
+    }
+}
+
+#[cfg_attr(target_arch = "wasm32", wasm_bindgen_test)]
+#[cfg_attr(not(target_arch = "wasm32"), test)]
+fn test_all_testcases() {
+    let testcases = get_testcases();
+    assert!(!testcases.is_empty(), "testcases.toml should contain testcases");
+
+    let failures: Vec<String> = testcases.iter().filter_map(|tc| run_testcase(tc).err()).collect();
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} testcases failed:\n{}",
+        failures.len(),
+        testcases.len(),
+        failures.join("\n")
+    );
+}
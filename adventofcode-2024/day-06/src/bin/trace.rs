@@ -0,0 +1,54 @@
+use day_06::trace::trace;
+use miette::{Context, IntoDiagnostic};
+use std::fs;
+use std::path::PathBuf;
+
+/// Writes the guard walk of `input.txt` as SVGs:
+/// `walk.svg`, `loop-NNNN.svg` for every loop-causing obstacle and one `frames/NNNNN.svg` per step of the walk.
+///
+/// `cargo run -p day-06 --bin trace -- [output-dir] [cell-size]`
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let output_dir = PathBuf::from(args.next().unwrap_or_else(|| "trace".to_string()));
+    let cell_size = match args.next() {
+        Some(cell_size) => cell_size
+            .parse()
+            .into_diagnostic()
+            .context("parse cell size")?,
+        None => 10,
+    };
+
+    let file = include_str!("../../input.txt");
+    let trace = trace(file);
+
+    let frames_dir = output_dir.join("frames");
+    fs::create_dir_all(&frames_dir)
+        .into_diagnostic()
+        .context("create output directory")?;
+
+    fs::write(output_dir.join("walk.svg"), trace.to_svg(cell_size)).into_diagnostic()?;
+    for loop_idx in 0..trace.loop_obstacles.len() {
+        let svg = trace
+            .loop_to_svg(loop_idx, cell_size)
+            .expect("loop index is in range");
+        fs::write(output_dir.join(format!("loop-{loop_idx:04}.svg")), svg).into_diagnostic()?;
+    }
+    for step in 0..trace.walk.len() {
+        fs::write(
+            frames_dir.join(format!("{step:05}.svg")),
+            trace.svg_frame(step, cell_size),
+        )
+        .into_diagnostic()?;
+    }
+
+    println!(
+        "wrote {} steps and {} loops to {}",
+        trace.walk.len(),
+        trace.loop_obstacles.len(),
+        output_dir.display()
+    );
+    Ok(())
+}
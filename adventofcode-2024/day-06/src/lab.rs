@@ -0,0 +1,187 @@
+use glam::IVec2;
+use rayon::prelude::*;
+
+/// Clockwise, starting with up - turning right is `(heading + 1) % 4`
+const HEADINGS: [IVec2; 4] = [
+    IVec2::new(0, -1),
+    IVec2::new(1, 0),
+    IVec2::new(0, 1),
+    IVec2::new(-1, 0),
+];
+
+fn heading_index(direction: IVec2) -> usize {
+    HEADINGS
+        .iter()
+        .position(|heading| *heading == direction)
+        .expect("guard should face one of the four directions")
+}
+
+/// Dense version of the occupancy map with a precomputed jump table:
+/// for every cell and heading, the cell where the guard stops in front of the next obstacle.
+pub(crate) struct Lab {
+    dimensions: IVec2,
+    obstacles: Vec<bool>,
+    /// `None` if the guard walks off the map
+    next_stop: Vec<[Option<IVec2>; 4]>,
+}
+
+impl Lab {
+    pub(crate) fn new(occupancy_map: &[Vec<bool>]) -> Self {
+        let dimensions = IVec2::new(occupancy_map[0].len() as i32, occupancy_map.len() as i32);
+        let obstacles = occupancy_map.iter().flatten().copied().collect();
+
+        let mut lab = Lab {
+            dimensions,
+            obstacles,
+            next_stop: vec![[None; 4]; (dimensions.x * dimensions.y) as usize],
+        };
+
+        for (heading_idx, heading) in HEADINGS.iter().enumerate() {
+            // visit the cells in front first, so that their stop is already known
+            let mut cells = lab.cells().collect::<Vec<_>>();
+            cells.sort_by_key(|cell| -cell.dot(*heading));
+
+            for cell in cells {
+                let ahead = cell + heading;
+                let stop = if !lab.in_bounds(ahead) {
+                    None
+                } else if lab.is_obstacle(ahead) {
+                    Some(cell)
+                } else {
+                    lab.next_stop[lab.index(ahead)][heading_idx]
+                };
+                let idx = lab.index(cell);
+                lab.next_stop[idx][heading_idx] = stop;
+            }
+        }
+
+        lab
+    }
+
+    fn cells(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (0..self.dimensions.y).flat_map(|y| (0..self.dimensions.x).map(move |x| IVec2::new(x, y)))
+    }
+
+    fn index(&self, cell: IVec2) -> usize {
+        (cell.y * self.dimensions.x + cell.x) as usize
+    }
+
+    fn in_bounds(&self, cell: IVec2) -> bool {
+        cell.x >= 0 && cell.y >= 0 && cell.x < self.dimensions.x && cell.y < self.dimensions.y
+    }
+
+    fn is_obstacle(&self, cell: IVec2) -> bool {
+        self.obstacles[self.index(cell)]
+    }
+
+    /// Where the guard stops when walking from `cell` in `heading` direction - `None` if the guard leaves the map.
+    fn next_stop(&self, cell: IVec2, heading_idx: usize, extra_obstacle: IVec2) -> Option<IVec2> {
+        let heading = HEADINGS[heading_idx];
+        let stop = self.next_stop[self.index(cell)][heading_idx];
+
+        // the extra obstacle only matters if it's straight ahead and before the regular stop
+        let to_extra = extra_obstacle - cell;
+        let is_ahead = to_extra.perp_dot(heading) == 0 && to_extra.dot(heading) > 0;
+        let is_before_stop =
+            stop.is_none_or(|stop| to_extra.dot(heading) <= (stop - cell).dot(heading));
+
+        if is_ahead && is_before_stop {
+            Some(extra_obstacle - heading)
+        } else {
+            stop
+        }
+    }
+
+    /// Follows the jump table from the given state and records every (stop, heading) in a bitset,
+    /// a state that is reached twice means the guard is stuck in a loop.
+    fn is_loop(
+        &self,
+        start: IVec2,
+        start_heading_idx: usize,
+        extra_obstacle: IVec2,
+        seen: &mut StateSet,
+    ) -> bool {
+        seen.clear();
+        let mut cell = start;
+        let mut heading_idx = start_heading_idx;
+
+        while let Some(stop) = self.next_stop(cell, heading_idx, extra_obstacle) {
+            cell = stop;
+            heading_idx = (heading_idx + 1) % 4;
+            if !seen.insert(self.index(cell) * 4 + heading_idx) {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Every cell of the original path (except the start) is a candidate for the extra obstacle.
+    /// The guard walks the original path until it's in front of the candidate for the first time,
+    /// so each trial can start right there instead of at the starting location.
+    fn obstacle_trials(&self, start: IVec2, start_direction: IVec2) -> Vec<(IVec2, usize, IVec2)> {
+        let mut trials = vec![];
+        let mut tried = vec![false; self.obstacles.len()];
+        tried[self.index(start)] = true;
+
+        let mut cell = start;
+        let mut heading_idx = heading_index(start_direction);
+        loop {
+            let ahead = cell + HEADINGS[heading_idx];
+            if !self.in_bounds(ahead) {
+                return trials;
+            }
+            if self.is_obstacle(ahead) {
+                heading_idx = (heading_idx + 1) % 4;
+                continue;
+            }
+            if !tried[self.index(ahead)] {
+                tried[self.index(ahead)] = true;
+                trials.push((cell, heading_idx, ahead));
+            }
+            cell = ahead;
+        }
+    }
+
+    /// All positions for an extra obstacle that trap the guard in a loop.
+    pub(crate) fn loop_obstacles(&self, start: IVec2, start_direction: IVec2) -> Vec<IVec2> {
+        let state_count = self.obstacles.len() * 4;
+
+        self.obstacle_trials(start, start_direction)
+            .into_par_iter()
+            .map_init(
+                || StateSet::new(state_count),
+                |seen, (cell, heading_idx, extra_obstacle)| {
+                    self.is_loop(cell, heading_idx, extra_obstacle, seen)
+                        .then_some(extra_obstacle)
+                },
+            )
+            .flatten()
+            .collect()
+    }
+}
+
+/// Dense bitset over (cell index, heading)
+struct StateSet {
+    words: Vec<u64>,
+}
+
+impl StateSet {
+    fn new(size: usize) -> Self {
+        StateSet {
+            words: vec![0; size.div_ceil(64)],
+        }
+    }
+
+    fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    /// `false` if the state was already present
+    fn insert(&mut self, state: usize) -> bool {
+        let mask = 1 << (state % 64);
+        let word = &mut self.words[state / 64];
+        let is_new = *word & mask == 0;
+        *word |= mask;
+        is_new
+    }
+}
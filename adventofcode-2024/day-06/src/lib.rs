@@ -2,8 +2,10 @@ use glam::IVec2;
 use std::collections::{HashMap, HashSet};
 use std::ops::Add;
 
+mod lab;
 pub mod part1;
 pub mod part2;
+pub mod trace;
 
 fn parse_map(input: &str) -> (Vec<Vec<bool>>, IVec2, IVec2) {
     let mut occupancy_map = Vec::new();
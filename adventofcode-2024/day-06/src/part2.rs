@@ -1,49 +1,22 @@
+use crate::lab::Lab;
+use crate::parse_map;
+#[cfg(test)]
 use glam::IVec2;
-use itertools::Itertools;
+#[cfg(test)]
 use std::collections::HashSet;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
     let (occupancy_map, starting_location, direction) = parse_map(input);
 
-    let height = occupancy_map.len();
-    let width = occupancy_map[0].len();
-
-    let map_dimensions = IVec2::new(width as i32, height as i32);
-
-    let is_in_bounds = |loc: IVec2| {
-        loc.x >= 0 && loc.y >= 0 && loc.x < map_dimensions.x && loc.y < map_dimensions.y
-    };
+    let lab = Lab::new(&occupancy_map);
+    let result = lab.loop_obstacles(starting_location, direction).len();
 
-    let (_visited, original_path) = walk_off_the_earth(
-        &occupancy_map,
-        &starting_location,
-        &direction,
-        None,
-        is_in_bounds,
-    );
-
-    let correct_obstacles = original_path
-        .into_iter()
-        .filter(|extra_obstacle| extra_obstacle != &starting_location)
-        //.inspect(|potential_extra_obstacle| { dbg!(potential_extra_obstacle); })
-        .filter_map(|extra_obstacle| {
-            let is_loop = find_loop(
-                &occupancy_map,
-                &starting_location,
-                &direction,
-                extra_obstacle,
-                is_in_bounds,
-            );
-            Some(extra_obstacle).filter(|_| is_loop)
-        })
-        //.inspect(|extra_obstacle| { dbg!(extra_obstacle); })
-        .collect_vec();
-
-    let result = correct_obstacles.len();
     Ok(result.to_string())
 }
 
+/// Reference implementation that simulates the whole walk step by step - used to verify the jump table in tests.
+#[cfg(test)]
 fn find_loop<F>(
     occupancy_map: &Vec<Vec<bool>>,
     starting_location: &IVec2,
@@ -77,128 +50,25 @@ where
     }
 }
 
-use crate::{parse_map, perform_step, walk_off_the_earth};
-use std::fmt::Write;
-
-fn generate_svg(
-    path: &[IVec2],
-    original_obstacles: &[IVec2],
-    new_obstacles: &[IVec2],
-    cell_size: i32,
-) -> String {
-    // Find bounds
-    let all_points: Vec<_> = path.iter().chain(new_obstacles.iter()).collect();
-
-    let min_x = all_points.iter().map(|p| p.x).min().unwrap_or(0) - 1;
-    let max_x = all_points.iter().map(|p| p.x).max().unwrap_or(0) + 1;
-    let min_y = all_points.iter().map(|p| p.y).min().unwrap_or(0) - 1;
-    let max_y = all_points.iter().map(|p| p.y).max().unwrap_or(0) + 1;
-
-    let width = (max_x - min_x + 1) * cell_size;
-    let height = (max_y - min_y + 1) * cell_size;
-
-    let mut svg = String::new();
-
-    // SVG header
-    writeln!(
-        &mut svg,
-        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\">",
-        min_x * cell_size,
-        min_y * cell_size,
-        width,
-        height
-    )
-    .unwrap();
-
-    // Grid
-    for x in min_x..=max_x {
-        writeln!(
-            &mut svg,
-            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#ddd\" stroke-width=\"1\"/>",
-            x * cell_size,
-            min_y * cell_size,
-            x * cell_size,
-            max_y * cell_size
-        )
-        .unwrap();
-    }
-    for y in min_y..=max_y {
-        writeln!(
-            &mut svg,
-            "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#ddd\" stroke-width=\"1\"/>",
-            min_x * cell_size,
-            y * cell_size,
-            max_x * cell_size,
-            y * cell_size
-        )
-        .unwrap();
-    }
-
-    // Obstacles
-    for pos in original_obstacles {
-        writeln!(
-            &mut svg,
-            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#666\"/>",
-            pos.x * cell_size,
-            pos.y * cell_size,
-            cell_size,
-            cell_size
-        )
-        .unwrap();
-    }
-
-    // Obstacles
-    for pos in new_obstacles {
-        writeln!(
-            &mut svg,
-            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"#FF0000\"/>",
-            pos.x * cell_size,
-            pos.y * cell_size,
-            cell_size,
-            cell_size
-        )
-        .unwrap();
-    }
-
-    // Function to get cell center
-    let center = |pos: &IVec2| {
-        (
-            pos.x * cell_size + cell_size / 2,
-            pos.y * cell_size + cell_size / 2,
-        )
-    };
-
-    // Main path lines
-    for window in path.windows(2) {
-        let (x1, y1) = center(&window[0]);
-        let (x2, y2) = center(&window[1]);
-        writeln!(&mut svg, "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#2196F3\" stroke-width=\"2\" opacity=\"0.8\"/>",
-                 x1, y1, x2, y2).unwrap();
-    }
-
-    // Main path points
-    for (idx, pos) in path.iter().enumerate() {
-        let x = pos.x * cell_size + (cell_size as f32 * 0.2) as i32;
-        let y = pos.y * cell_size + (cell_size as f32 * 0.2) as i32;
-        let size = (cell_size as f32 * 0.6) as i32;
-        let color = if idx == 0 { "#00FF00" } else { "#2196F3" };
-        writeln!(
-            &mut svg,
-            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" opacity=\"0.8\"/>",
-            x, y, size, size, color
-        )
-        .unwrap();
-    }
-
-    // Close SVG
-    writeln!(&mut svg, "</svg>").unwrap();
-
-    svg
-}
+#[cfg(test)]
+use crate::perform_step;
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::walk_off_the_earth;
+    use rstest::rstest;
+
+    const EXAMPLE: &str = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#..."#;
 
     #[test]
     fn test_process() -> miette::Result<()> {
@@ -245,13 +115,6 @@ mod tests {
             loc.x >= 0 && loc.y >= 0 && loc.x < map_dimensions.x && loc.y < map_dimensions.y
         };
 
-        let (visited, original_path) = walk_off_the_earth(
-            &occupancy_map,
-            &starting_location,
-            &direction,
-            None,
-            is_in_bounds,
-        );
         let extra_obstacle = IVec2::new(3, 6);
         let is_loop = find_loop(
             &occupancy_map,
@@ -265,4 +128,45 @@ mod tests {
 
         Ok(())
     }
+
+    #[rstest]
+    #[case(EXAMPLE)]
+    // dead end: the guard has to turn twice
+    #[case("..#..\n.#^#.\n.....\n.....")]
+    #[case("#....\n...#.\n.....\n^....\n..#..")]
+    #[case(".#....\n.....#\n......\n......\n.^..#.")]
+    fn test_jump_table_matches_find_loop(#[case] input: &str) {
+        let (occupancy_map, starting_location, direction) = parse_map(input);
+        let map_dimensions = IVec2::new(occupancy_map[0].len() as i32, occupancy_map.len() as i32);
+        let is_in_bounds = |loc: IVec2| {
+            loc.x >= 0 && loc.y >= 0 && loc.x < map_dimensions.x && loc.y < map_dimensions.y
+        };
+
+        let (_, original_path) = walk_off_the_earth(
+            &occupancy_map,
+            &starting_location,
+            &direction,
+            None,
+            is_in_bounds,
+        );
+        let mut expected = original_path
+            .into_iter()
+            .filter(|extra_obstacle| extra_obstacle != &starting_location)
+            .filter(|extra_obstacle| {
+                find_loop(
+                    &occupancy_map,
+                    &starting_location,
+                    &direction,
+                    *extra_obstacle,
+                    is_in_bounds,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        let mut actual = Lab::new(&occupancy_map).loop_obstacles(starting_location, direction);
+
+        expected.sort_by_key(|obstacle| (obstacle.y, obstacle.x));
+        actual.sort_by_key(|obstacle| (obstacle.y, obstacle.x));
+        assert_eq!(expected, actual);
+    }
 }
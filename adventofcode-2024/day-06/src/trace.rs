@@ -0,0 +1,359 @@
+use crate::lab::Lab;
+use crate::{parse_map, perform_step};
+use glam::IVec2;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GuardState {
+    pub position: IVec2,
+    pub heading: IVec2,
+}
+
+impl GuardState {
+    /// the guard as drawn in the puzzle, e.g. `^`
+    pub fn glyph(&self) -> char {
+        match (self.heading.x, self.heading.y) {
+            (0, -1) => '^',
+            (1, 0) => '>',
+            (0, 1) => 'v',
+            _ => '<',
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoopObstacle {
+    pub position: IVec2,
+    /// the states the guard repeats forever once this obstacle has been placed
+    pub cycle: Vec<GuardState>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardTrace {
+    pub dimensions: IVec2,
+    pub obstacles: Vec<IVec2>,
+    /// Every state of the original walk, until the guard leaves the map.
+    /// Turning on the spot is a separate state. If the guard never leaves, the walk stops before the first repeated state.
+    pub walk: Vec<GuardState>,
+    /// all positions for an extra obstacle that trap the guard (part 2), in the order the guard reaches them
+    pub loop_obstacles: Vec<LoopObstacle>,
+}
+
+/// Traces the guard through the lab and finds every loop-causing obstacle together with the resulting cycle.
+pub fn trace(input: &str) -> GuardTrace {
+    let (occupancy_map, starting_location, direction) = parse_map(input);
+    let dimensions = IVec2::new(occupancy_map[0].len() as i32, occupancy_map.len() as i32);
+
+    let obstacles = occupancy_map
+        .iter()
+        .enumerate()
+        .flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, is_obstacle)| **is_obstacle)
+                .map(move |(x, _)| IVec2::new(x as i32, y as i32))
+        })
+        .collect();
+
+    let start = GuardState {
+        position: starting_location,
+        heading: direction,
+    };
+    let (walk, _) = simulate(&occupancy_map, dimensions, start, None);
+
+    let loop_obstacles = Lab::new(&occupancy_map)
+        .loop_obstacles(starting_location, direction)
+        .into_iter()
+        .map(|position| {
+            let (states, cycle_start) = simulate(&occupancy_map, dimensions, start, Some(position));
+            LoopObstacle {
+                position,
+                cycle: states[cycle_start.unwrap_or(states.len())..].to_vec(),
+            }
+        })
+        .collect();
+
+    GuardTrace {
+        dimensions,
+        obstacles,
+        walk,
+        loop_obstacles,
+    }
+}
+
+/// Walks step by step until the guard leaves the map or repeats a state.
+/// Returns the states and - for a loop - the index of the first state of the cycle.
+fn simulate(
+    occupancy_map: &Vec<Vec<bool>>,
+    dimensions: IVec2,
+    start: GuardState,
+    extra_obstacle: Option<IVec2>,
+) -> (Vec<GuardState>, Option<usize>) {
+    let in_bounds =
+        |loc: IVec2| loc.x >= 0 && loc.y >= 0 && loc.x < dimensions.x && loc.y < dimensions.y;
+
+    let mut states = vec![start];
+    let mut seen = HashMap::from([(start, 0)]);
+    let mut state = start;
+    loop {
+        let (position, heading) = perform_step(
+            occupancy_map,
+            &state.position,
+            &state.heading,
+            extra_obstacle,
+        );
+        state = GuardState { position, heading };
+        if !in_bounds(position) {
+            return (states, None);
+        }
+        if let Some(cycle_start) = seen.get(&state) {
+            return (states, Some(*cycle_start));
+        }
+        seen.insert(state, states.len());
+        states.push(state);
+    }
+}
+
+impl GuardTrace {
+    /// The map in puzzle notation after `step` states of the walk: visited cells are `X`, the guard is drawn with its heading.
+    /// `frame(0)` reproduces the input.
+    pub fn frame(&self, step: usize) -> String {
+        let mut grid = vec![vec!['.'; self.dimensions.x as usize]; self.dimensions.y as usize];
+        for obstacle in &self.obstacles {
+            grid[obstacle.y as usize][obstacle.x as usize] = '#';
+        }
+
+        let step = step.min(self.walk.len().saturating_sub(1));
+        for state in &self.walk[..step] {
+            grid[state.position.y as usize][state.position.x as usize] = 'X';
+        }
+        if let Some(guard) = self.walk.get(step) {
+            grid[guard.position.y as usize][guard.position.x as usize] = guard.glyph();
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// one text frame per state of the walk, e.g. for a terminal animation
+    pub fn frames(&self) -> impl Iterator<Item = String> + '_ {
+        (0..self.walk.len()).map(|step| self.frame(step))
+    }
+
+    /// The whole walk with all loop-causing obstacles in red.
+    pub fn to_svg(&self, cell_size: i32) -> String {
+        let new_obstacles = self
+            .loop_obstacles
+            .iter()
+            .map(|loop_obstacle| loop_obstacle.position)
+            .collect::<Vec<_>>();
+        self.render_svg(&self.walk, &new_obstacles, &[], cell_size)
+    }
+
+    /// The walk up to (and including) `step` - e.g. to render an animation frame by frame.
+    pub fn svg_frame(&self, step: usize, cell_size: i32) -> String {
+        let end = (step + 1).min(self.walk.len());
+        self.render_svg(&self.walk[..end], &[], &[], cell_size)
+    }
+
+    /// The walk with a single loop-causing obstacle and the cycle it causes in orange.
+    pub fn loop_to_svg(&self, loop_idx: usize, cell_size: i32) -> Option<String> {
+        let loop_obstacle = self.loop_obstacles.get(loop_idx)?;
+        Some(self.render_svg(
+            &self.walk,
+            &[loop_obstacle.position],
+            &loop_obstacle.cycle,
+            cell_size,
+        ))
+    }
+
+    fn render_svg(
+        &self,
+        path: &[GuardState],
+        new_obstacles: &[IVec2],
+        cycle: &[GuardState],
+        cell_size: i32,
+    ) -> String {
+        let width = self.dimensions.x * cell_size;
+        let height = self.dimensions.y * cell_size;
+
+        let mut svg = String::new();
+
+        // SVG header
+        writeln!(
+            &mut svg,
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\">",
+            width, height
+        )
+        .unwrap();
+
+        // Grid
+        for x in 0..=self.dimensions.x {
+            writeln!(
+                &mut svg,
+                "  <line x1=\"{}\" y1=\"0\" x2=\"{}\" y2=\"{}\" stroke=\"#ddd\" stroke-width=\"1\"/>",
+                x * cell_size,
+                x * cell_size,
+                height
+            )
+            .unwrap();
+        }
+        for y in 0..=self.dimensions.y {
+            writeln!(
+                &mut svg,
+                "  <line x1=\"0\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"#ddd\" stroke-width=\"1\"/>",
+                y * cell_size,
+                width,
+                y * cell_size
+            )
+            .unwrap();
+        }
+
+        // Obstacles
+        for (positions, color) in [
+            (self.obstacles.as_slice(), "#666"),
+            (new_obstacles, "#FF0000"),
+        ] {
+            for pos in positions {
+                writeln!(
+                    &mut svg,
+                    "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\"/>",
+                    pos.x * cell_size,
+                    pos.y * cell_size,
+                    cell_size,
+                    cell_size,
+                    color
+                )
+                .unwrap();
+            }
+        }
+
+        // Function to get cell center
+        let center = |pos: &IVec2| {
+            (
+                pos.x * cell_size + cell_size / 2,
+                pos.y * cell_size + cell_size / 2,
+            )
+        };
+
+        // Path lines
+        for (states, color) in [(path, "#2196F3"), (cycle, "#FF9800")] {
+            for window in states.windows(2) {
+                let (x1, y1) = center(&window[0].position);
+                let (x2, y2) = center(&window[1].position);
+                writeln!(
+                    &mut svg,
+                    "  <line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" stroke=\"{}\" stroke-width=\"2\" opacity=\"0.8\"/>",
+                    x1, y1, x2, y2, color
+                )
+                .unwrap();
+            }
+        }
+
+        // Start and current position of the guard
+        for (state, color) in [(path.first(), "#00FF00"), (path.last(), "#2196F3")] {
+            let Some(state) = state else {
+                continue;
+            };
+            let (x, y) = center(&state.position);
+            writeln!(
+                &mut svg,
+                "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" dominant-baseline=\"central\" fill=\"{}\">{}</text>",
+                x,
+                y,
+                cell_size,
+                color,
+                state.glyph()
+            )
+            .unwrap();
+        }
+
+        // Close SVG
+        writeln!(&mut svg, "</svg>").unwrap();
+
+        svg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = r#"....#.....
+.........#
+..........
+..#.......
+.......#..
+..........
+.#..^.....
+........#.
+#.........
+......#..."#;
+
+    #[test]
+    fn test_trace() {
+        let trace = trace(EXAMPLE);
+
+        assert_eq!(trace.dimensions, IVec2::new(10, 10));
+        assert_eq!(trace.obstacles.len(), 8);
+        assert_eq!(trace.walk.first().unwrap().glyph(), '^');
+        assert_eq!(
+            trace.walk.last(),
+            Some(&GuardState {
+                position: IVec2::new(7, 9),
+                heading: IVec2::new(0, 1)
+            })
+        );
+
+        let mut loop_obstacle_positions = trace
+            .loop_obstacles
+            .iter()
+            .map(|l| (l.position.x, l.position.y))
+            .collect::<Vec<_>>();
+        loop_obstacle_positions.sort();
+        assert_eq!(
+            loop_obstacle_positions,
+            vec![(1, 8), (3, 6), (3, 8), (6, 7), (7, 7), (7, 9)]
+        );
+
+        for loop_obstacle in &trace.loop_obstacles {
+            assert!(!loop_obstacle.cycle.is_empty());
+            // the guard turns in front of the new obstacle as part of the cycle
+            assert!(loop_obstacle
+                .cycle
+                .iter()
+                .any(|state| state.position + state.heading == loop_obstacle.position));
+        }
+    }
+
+    #[test]
+    fn test_frames() {
+        let trace = trace(EXAMPLE);
+
+        assert_eq!(trace.frame(0), EXAMPLE);
+        assert_eq!(trace.frames().count(), trace.walk.len());
+
+        let last_frame = trace.frames().last().unwrap();
+        assert_eq!(last_frame.chars().filter(|c| *c == 'X').count(), 40);
+        assert!(last_frame.ends_with("......#v.."));
+    }
+
+    #[test]
+    fn test_svg() {
+        let trace = trace(EXAMPLE);
+
+        let svg = trace.to_svg(10);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("#FF0000").count(), 6);
+
+        let loop_svg = trace.loop_to_svg(0, 10).unwrap();
+        assert_eq!(loop_svg.matches("#FF0000").count(), 1);
+        assert!(loop_svg.contains("#FF9800"));
+        assert!(trace.loop_to_svg(6, 10).is_none());
+
+        assert_eq!(trace.svg_frame(0, 10).matches("#2196F3").count(), 1);
+    }
+}
@@ -10,7 +10,7 @@ fn part1() {
     part1::process(divan::black_box(include_str!("../input.txt",))).unwrap();
 }
 
-#[divan::bench(sample_count = 2)]
+#[divan::bench]
 fn part2() {
     part2::process(divan::black_box(include_str!("../input.txt",))).unwrap();
 }
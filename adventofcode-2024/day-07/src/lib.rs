@@ -1,33 +1,285 @@
-use itertools::Itertools;
+use miette::miette;
 use nom::bytes::complete::tag;
 use nom::character::complete;
 use nom::character::complete::{char, line_ending};
 use nom::multi::separated_list1;
 use nom::sequence::separated_pair;
 use nom::IResult;
+use rayon::prelude::*;
+use std::fmt::{Display, Formatter};
 
+pub use crate::operator::{parse_operators, Operator};
+
+pub mod operator;
 pub mod part1;
 pub mod part2;
 
+/// Sums the test values of all equations that can be solved with the given operator set.
+pub fn process_parameterized(
+    input: &str,
+    allowed_operators: &[Operator],
+) -> miette::Result<String> {
+    let (_, calibration_equations) = parse(input).map_err(|e| miette!("parse failed {}", e))?;
+
+    Ok(calc_result(&calibration_equations, allowed_operators).to_string())
+}
+
+/// `args` is a space separated operator set, e.g. `+ * ||` or `- ^ ||2`
+pub fn process_with_args(input: &str, args: &str) -> miette::Result<String> {
+    let (_, allowed_operators) =
+        parse_operators(args.trim()).map_err(|e| miette!("arg-parse failed {}", e))?;
+
+    process_parameterized(input, &allowed_operators)
+}
+
 fn calc_result(
-    calibration_equations: Vec<CalibrationEquation>,
-    allowed_operators: Vec<Operator>,
+    calibration_equations: &[CalibrationEquation],
+    allowed_operators: &[Operator],
 ) -> i64 {
-    let mut result = 0;
+    calibration_equations
+        .par_iter()
+        .filter(|calibration_equation| calibration_equation.is_solvable(allowed_operators))
+        .map(|calibration_equation| calibration_equation.test_value)
+        .sum()
+}
 
-    for calibration_equation in calibration_equations {
-        let all_possible_results = eval(calibration_equation.clone(), &allowed_operators);
-        if all_possible_results.contains(&calibration_equation.test_value) {
-            result += calibration_equation.test_value
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibrationEquation {
+    pub test_value: i64,
+    pub operands: Vec<i64>,
+}
+
+impl CalibrationEquation {
+    /// Works backwards from the `test_value`: the last operator must have produced the test value from the last operand,
+    /// so every operator that can't be undone (not divisible, not a suffix, negative difference) prunes that branch.
+    /// Negative differences are only pruned if no operand is negative.
+    pub fn is_solvable(&self, allowed_operators: &[Operator]) -> bool {
+        let monotonic = operator::is_monotonic(allowed_operators, &self.operands);
+        is_solvable_reverse(
+            self.test_value,
+            &self.operands,
+            allowed_operators,
+            monotonic,
+        )
+    }
+
+    /// The operator sequences that solve the equation - at most `limit` of them.
+    pub fn witnesses(&self, allowed_operators: &[Operator], limit: WitnessLimit) -> Vec<Witness> {
+        let monotonic = operator::is_monotonic(allowed_operators, &self.operands);
+        let max_count = match limit {
+            WitnessLimit::First => 1,
+            WitnessLimit::All => usize::MAX,
+        };
+
+        let mut operators_reversed = Vec::with_capacity(self.operands.len());
+        let mut witnesses = vec![];
+        collect_witnesses_reverse(
+            self.test_value,
+            &self.operands,
+            allowed_operators,
+            monotonic,
+            max_count,
+            &mut operators_reversed,
+            &mut witnesses,
+        );
+
+        witnesses
+            .into_iter()
+            .map(|operators| Witness {
+                test_value: self.test_value,
+                operands: self.operands.clone(),
+                operators,
+            })
+            .collect()
+    }
+}
+
+fn is_solvable_reverse(
+    target: i64,
+    operands: &[i64],
+    allowed_operators: &[Operator],
+    monotonic: bool,
+) -> bool {
+    match operands {
+        [] => false,
+        [first] => *first == target,
+        [rest @ .., last] => allowed_operators.iter().any(|operator| {
+            if operator.absorbs(target, *last) {
+                return is_evaluable_forward(rest[0], &rest[1..], allowed_operators);
+            }
+            operator
+                .undo(target, *last, monotonic)
+                .is_some_and(|previous| {
+                    is_solvable_reverse(previous, rest, allowed_operators, monotonic)
+                })
+        }),
+    }
+}
+
+/// Whether some operator sequence evaluates `acc` and the `operands` without overflowing -
+/// any result will do once the remaining operands multiply it by 0.
+fn is_evaluable_forward(acc: i64, operands: &[i64], allowed_operators: &[Operator]) -> bool {
+    match operands {
+        [] => true,
+        [next, rest @ ..] => allowed_operators.iter().any(|operator| {
+            operator
+                .perform(acc, *next)
+                .is_some_and(|acc| is_evaluable_forward(acc, rest, allowed_operators))
+        }),
+    }
+}
+
+/// Same search as [is_solvable_reverse], but remembers the undone operators (last operator first).
+fn collect_witnesses_reverse(
+    target: i64,
+    operands: &[i64],
+    allowed_operators: &[Operator],
+    monotonic: bool,
+    max_count: usize,
+    operators_reversed: &mut Vec<Operator>,
+    witnesses: &mut Vec<Vec<Operator>>,
+) {
+    match operands {
+        [] => {}
+        [first] => {
+            if *first == target {
+                witnesses.push(operators_reversed.iter().rev().copied().collect());
+            }
+        }
+        [rest @ .., last] => {
+            for operator in allowed_operators {
+                if witnesses.len() >= max_count {
+                    return;
+                }
+                if operator.absorbs(target, *last) {
+                    operators_reversed.push(*operator);
+                    collect_evaluable_forward(
+                        rest[0],
+                        &rest[1..],
+                        allowed_operators,
+                        max_count,
+                        &mut vec![],
+                        operators_reversed,
+                        witnesses,
+                    );
+                    operators_reversed.pop();
+                } else if let Some(previous) = operator.undo(target, *last, monotonic) {
+                    operators_reversed.push(*operator);
+                    collect_witnesses_reverse(
+                        previous,
+                        rest,
+                        allowed_operators,
+                        monotonic,
+                        max_count,
+                        operators_reversed,
+                        witnesses,
+                    );
+                    operators_reversed.pop();
+                }
+            }
         }
     }
-    result
 }
 
-#[derive(Debug, Clone)]
-struct CalibrationEquation {
-    test_value: i64,
-    operands: Vec<i64>,
+/// Same search as [is_evaluable_forward], but remembers every operator sequence (first operator first)
+/// and completes it with the already undone `operators_reversed`.
+fn collect_evaluable_forward(
+    acc: i64,
+    operands: &[i64],
+    allowed_operators: &[Operator],
+    max_count: usize,
+    operators: &mut Vec<Operator>,
+    operators_reversed: &[Operator],
+    witnesses: &mut Vec<Vec<Operator>>,
+) {
+    match operands {
+        [] => witnesses.push(
+            operators
+                .iter()
+                .chain(operators_reversed.iter().rev())
+                .copied()
+                .collect(),
+        ),
+        [next, rest @ ..] => {
+            for operator in allowed_operators {
+                if witnesses.len() >= max_count {
+                    return;
+                }
+                if let Some(acc) = operator.perform(acc, *next) {
+                    operators.push(*operator);
+                    collect_evaluable_forward(
+                        acc,
+                        rest,
+                        allowed_operators,
+                        max_count,
+                        operators,
+                        operators_reversed,
+                        witnesses,
+                    );
+                    operators.pop();
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WitnessLimit {
+    First,
+    All,
+}
+
+/// An operator sequence that solves a [CalibrationEquation].
+/// Rendered left to right like the puzzle evaluates it, e.g. `3267 = 81 + 40 * 27`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness {
+    pub test_value: i64,
+    pub operands: Vec<i64>,
+    /// `operators[i]` combines the intermediate result with `operands[i + 1]`
+    pub operators: Vec<Operator>,
+}
+
+impl Witness {
+    /// Evaluates the expression left to right - `None` on overflow.
+    pub fn evaluate(&self) -> Option<i64> {
+        let (first, rest) = self.operands.split_first()?;
+        rest.iter()
+            .zip(self.operators.iter())
+            .try_fold(*first, |acc, (operand, operator)| {
+                operator.perform(acc, *operand)
+            })
+    }
+}
+
+impl Display for Witness {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} =", self.test_value)?;
+        for (idx, operand) in self.operands.iter().enumerate() {
+            if let Some(operator) = idx
+                .checked_sub(1)
+                .and_then(|op_idx| self.operators.get(op_idx))
+            {
+                write!(f, " {operator}")?;
+            }
+            write!(f, " {operand}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The witnesses of every solvable equation in the input, in input order. Unsolvable equations are skipped.
+pub fn find_witnesses(
+    input: &str,
+    allowed_operators: &[Operator],
+    limit: WitnessLimit,
+) -> miette::Result<Vec<Vec<Witness>>> {
+    let (_, calibration_equations) = parse(input).map_err(|e| miette!("parse failed {}", e))?;
+
+    Ok(calibration_equations
+        .par_iter()
+        .map(|calibration_equation| calibration_equation.witnesses(allowed_operators, limit))
+        .filter(|witnesses| !witnesses.is_empty())
+        .collect())
 }
 
 fn calibration_equation_parser(input: &str) -> IResult<&str, CalibrationEquation> {
@@ -50,69 +302,226 @@ fn parse(input: &str) -> IResult<&str, Vec<CalibrationEquation>> {
     separated_list1(line_ending, calibration_equation_parser)(input)
 }
 
-fn create_eval_list(
-    calibration_equation: &CalibrationEquation,
-    allowed_operators: &[Operator],
-) -> (i64, Vec<Vec<(Operator, i64)>>) {
-    let first = calibration_equation.operands[0];
+/// The original solver that evaluates every combination of operators - used as an oracle in tests.
+#[cfg(test)]
+mod naive {
+    use super::{CalibrationEquation, Operator};
+    use itertools::Itertools;
 
-    let rest = &calibration_equation.operands[1..];
+    pub(crate) fn calc_result(
+        calibration_equations: Vec<CalibrationEquation>,
+        allowed_operators: Vec<Operator>,
+    ) -> i64 {
+        let mut result = 0;
 
-    let all_operators = allowed_operators;
+        for calibration_equation in calibration_equations {
+            let all_possible_results = eval(calibration_equation.clone(), &allowed_operators);
+            if all_possible_results.contains(&calibration_equation.test_value) {
+                result += calibration_equation.test_value
+            }
+        }
+        result
+    }
 
-    let operations = get_all_combinations(rest.to_vec(), all_operators);
+    fn create_eval_list(
+        calibration_equation: &CalibrationEquation,
+        allowed_operators: &[Operator],
+    ) -> (i64, Vec<Vec<(Operator, i64)>>) {
+        let first = calibration_equation.operands[0];
 
-    (first, operations)
-}
+        let rest = &calibration_equation.operands[1..];
 
-fn get_all_combinations(operands: Vec<i64>, operators: &[Operator]) -> Vec<Vec<(Operator, i64)>> {
-    // Create a vector of operator references n times (where n is the number of operands)
-    let operator_sets = std::iter::repeat(operators.iter())
-        .take(operands.len())
-        .collect::<Vec<_>>();
+        let all_operators = allowed_operators;
 
-    // Get all possible combinations of operators
-    let operations = operator_sets
-        .into_iter()
-        .multi_cartesian_product()
-        .map(|ops| {
-            ops.into_iter()
-                .zip(operands.iter())
-                .map(|(op, num)| (op.clone(), *num))
-                .collect()
-        })
-        .collect();
+        let operations = get_all_combinations(rest.to_vec(), all_operators);
 
-    operations
-}
+        (first, operations)
+    }
 
-fn eval(calibration_equation: CalibrationEquation, allowed_operators: &[Operator]) -> Vec<i64> {
-    let (first, permutations) = create_eval_list(&calibration_equation, allowed_operators);
+    pub(crate) fn get_all_combinations(
+        operands: Vec<i64>,
+        operators: &[Operator],
+    ) -> Vec<Vec<(Operator, i64)>> {
+        // Create a vector of operator references n times (where n is the number of operands)
+        let operator_sets =
+            std::iter::repeat_n(operators.iter(), operands.len()).collect::<Vec<_>>();
 
-    permutations
-        .iter()
-        .map(|permutation| {
-            let result = permutation.iter().fold(first, |acc, (operator, operand)| {
-                operator.perform(acc, *operand)
-            });
-            result
-        })
-        .collect_vec()
-}
+        // Get all possible combinations of operators
+        operator_sets
+            .into_iter()
+            .multi_cartesian_product()
+            .map(|ops| {
+                ops.into_iter()
+                    .zip(operands.iter())
+                    .map(|(op, num)| (*op, *num))
+                    .collect()
+            })
+            .collect()
+    }
 
-#[derive(Debug, Clone, PartialOrd, PartialEq)]
-enum Operator {
-    Add,
-    Multiply,
-    Concat,
+    pub(crate) fn eval(
+        calibration_equation: CalibrationEquation,
+        allowed_operators: &[Operator],
+    ) -> Vec<i64> {
+        let (first, permutations) = create_eval_list(&calibration_equation, allowed_operators);
+
+        permutations
+            .iter()
+            .filter_map(|permutation| {
+                permutation
+                    .iter()
+                    .try_fold(first, |acc, (operator, operand)| {
+                        operator.perform(acc, *operand)
+                    })
+            })
+            .collect_vec()
+    }
 }
 
-impl Operator {
-    pub(crate) fn perform(&self, p0: i64, p1: i64) -> i64 {
-        match self {
-            Operator::Add => p0 + p1,
-            Operator::Multiply => p0 * p1,
-            Operator::Concat => format!("{p0}{p1}").parse::<i64>().unwrap(),
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const EXAMPLE: &str = r#"190: 10 19
+3267: 81 40 27
+83: 17 5
+156: 15 6
+7290: 6 8 6 15
+161011: 16 10 13
+192: 17 8 14
+21037: 9 7 18 13
+292: 11 6 16 20"#;
+
+    #[rstest]
+    #[case(vec![Operator::Add, Operator::Multiply])]
+    #[case(vec![Operator::Add, Operator::Multiply, Operator::CONCAT])]
+    #[case(vec![Operator::CONCAT])]
+    #[case(vec![Operator::Add, Operator::Subtract, Operator::Xor])]
+    #[case(vec![Operator::Multiply, Operator::Subtract, Operator::Concat { base: 2 }])]
+    #[case(vec![Operator::Add, Operator::Multiply, Operator::Concat { base: 16 }])]
+    fn test_reverse_solver_matches_naive(#[case] operators: Vec<Operator>) {
+        let (_, calibration_equations) = parse(EXAMPLE).unwrap();
+
+        for calibration_equation in calibration_equations.iter() {
+            let expected = naive::eval(calibration_equation.clone(), &operators)
+                .contains(&calibration_equation.test_value);
+            assert_eq!(
+                expected,
+                calibration_equation.is_solvable(&operators),
+                "{calibration_equation:?}"
+            );
+        }
+
+        assert_eq!(
+            naive::calc_result(calibration_equations.clone(), operators.clone()),
+            calc_result(&calibration_equations, &operators)
+        );
+    }
+
+    #[rstest]
+    // negative operands
+    #[case("5: -1 6", vec![Operator::Add, Operator::Multiply])]
+    #[case("-9223372036854775808: 3 -1", vec![Operator::Add, Operator::Multiply])]
+    #[case("-6: 2 -3\n6: -2 -3\n-1: -3 2", vec![Operator::Add, Operator::Multiply, Operator::CONCAT])]
+    #[case("5: -1 6\n-7: -3 4", vec![Operator::Subtract, Operator::Xor])]
+    // multiplying by 0 at the end accepts any prefix ...
+    #[case("0: 5 0\n0: 3 4 0", vec![Operator::Multiply])]
+    #[case("7: 7 0 7\n0: 3 4 0\n0: 0 0", vec![Operator::Add, Operator::Multiply])]
+    #[case("0: 9223372036854775807 2 0\n0: 9223372036854775807 2 2 0", vec![Operator::Multiply, Operator::Subtract])]
+    // ... unless every prefix overflows
+    #[case("0: 9223372036854775807 2 0", vec![Operator::Multiply])]
+    fn test_reverse_solver_matches_naive_on_edge_cases(
+        #[case] input: &str,
+        #[case] operators: Vec<Operator>,
+    ) {
+        let (_, calibration_equations) = parse(input).unwrap();
+
+        for calibration_equation in calibration_equations.iter() {
+            let expected = naive::eval(calibration_equation.clone(), &operators)
+                .into_iter()
+                .filter(|result| *result == calibration_equation.test_value)
+                .count();
+            assert_eq!(
+                expected > 0,
+                calibration_equation.is_solvable(&operators),
+                "{calibration_equation:?}"
+            );
+            let witnesses = calibration_equation.witnesses(&operators, WitnessLimit::All);
+            assert_eq!(expected, witnesses.len(), "{calibration_equation:?}");
+            assert!(witnesses
+                .iter()
+                .all(|witness| witness.evaluate() == Some(calibration_equation.test_value)));
         }
     }
+
+    #[test]
+    fn test_witnesses() {
+        let (_, calibration_equations) = parse(EXAMPLE).unwrap();
+        let operators = [Operator::Add, Operator::Multiply, Operator::CONCAT];
+
+        let all = calibration_equations[1].witnesses(&operators, WitnessLimit::All);
+        assert_eq!(
+            all.iter().map(|w| w.to_string()).collect::<Vec<_>>(),
+            vec!["3267 = 81 * 40 + 27", "3267 = 81 + 40 * 27"]
+        );
+        assert_eq!(
+            calibration_equations[1].witnesses(&operators, WitnessLimit::First),
+            all[..1]
+        );
+        assert!(calibration_equations[2]
+            .witnesses(&operators, WitnessLimit::All)
+            .is_empty());
+
+        let first = find_witnesses(EXAMPLE, &operators, WitnessLimit::First).unwrap();
+        assert_eq!(
+            first.iter().map(|w| w[0].to_string()).collect::<Vec<_>>(),
+            vec![
+                "190 = 10 * 19",
+                "3267 = 81 * 40 + 27",
+                "156 = 15 || 6",
+                "7290 = 6 * 8 || 6 * 15",
+                "192 = 17 || 8 + 14",
+                "292 = 11 + 6 * 16 + 20"
+            ]
+        );
+    }
+
+    #[rstest]
+    #[case(vec![Operator::Add, Operator::Multiply, Operator::CONCAT])]
+    #[case(vec![Operator::Subtract, Operator::Xor, Operator::Concat { base: 3 }])]
+    fn test_all_witnesses_evaluate_to_test_value(#[case] operators: Vec<Operator>) {
+        let (_, calibration_equations) = parse(EXAMPLE).unwrap();
+
+        for calibration_equation in calibration_equations {
+            let witnesses = calibration_equation.witnesses(&operators, WitnessLimit::All);
+            let expected_count = naive::eval(calibration_equation.clone(), &operators)
+                .into_iter()
+                .filter(|result| *result == calibration_equation.test_value)
+                .count();
+            assert_eq!(expected_count, witnesses.len(), "{calibration_equation:?}");
+
+            for witness in witnesses {
+                assert_eq!(
+                    Some(calibration_equation.test_value),
+                    witness.evaluate(),
+                    "{witness}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_process_with_args() -> miette::Result<()> {
+        assert_eq!("3749", process_with_args(EXAMPLE, "+ *")?);
+        assert_eq!("11387", process_with_args(EXAMPLE, "+ * ||")?);
+        assert!(process_with_args(EXAMPLE, "/").is_err());
+        assert_eq!("5", process_with_args("5: -1 6", "+ *")?);
+        assert_eq!("0", process_with_args("0: 5 0\n0: 3 4 0", "*")?);
+        assert_eq!("7", process_with_args("7: 7 0 7", "+ *")?);
+        assert!(process_with_args(EXAMPLE, "+ * /").is_err());
+        assert!(process_with_args(EXAMPLE, "||1").is_err());
+        assert_eq!("11387", process_with_args(EXAMPLE, " + * || ")?);
+        Ok(())
+    }
 }
@@ -0,0 +1,212 @@
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete;
+use nom::character::complete::{char, space1};
+use nom::combinator::{all_consuming, map, opt, value, verify};
+use nom::multi::separated_list1;
+use nom::sequence::preceded;
+use nom::IResult;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operator {
+    Add,
+    Multiply,
+    /// `12 || 345 = 12345` - digits are concatenated in the given base
+    Concat {
+        base: i64,
+    },
+    Subtract,
+    Xor,
+}
+
+impl Operator {
+    pub const CONCAT: Operator = Operator::Concat { base: 10 };
+
+    /// `None` if the result overflows or the operator isn't defined for the operands (concatenating negative numbers).
+    pub fn perform(&self, p0: i64, p1: i64) -> Option<i64> {
+        match self {
+            Operator::Add => p0.checked_add(p1),
+            Operator::Multiply => p0.checked_mul(p1),
+            Operator::Concat { base } => {
+                if p0 < 0 || p1 < 0 {
+                    return None;
+                }
+                p0.checked_mul(next_power(p1, *base)?)?.checked_add(p1)
+            }
+            Operator::Subtract => p0.checked_sub(p1),
+            Operator::Xor => Some(p0 ^ p1),
+        }
+    }
+
+    /// Finds `p0` with `p0 <op> p1 == result` - if there is exactly one, see [Operator::absorbs] for multiplying by 0.
+    /// With `monotonic` operators and operands no intermediate result can be negative, which prunes additions early.
+    pub(crate) fn undo(&self, result: i64, p1: i64, monotonic: bool) -> Option<i64> {
+        match self {
+            Operator::Add => result.checked_sub(p1).filter(|p0| !monotonic || *p0 >= 0),
+            Operator::Multiply => result
+                .checked_rem(p1)
+                .filter(|remainder| *remainder == 0)
+                .and_then(|_| result.checked_div(p1)),
+            Operator::Concat { base } => {
+                let power = next_power(p1, *base)?;
+                (p1 >= 0 && result >= p1 && result % power == p1).then(|| result / power)
+            }
+            Operator::Subtract => result.checked_add(p1),
+            Operator::Xor => Some(result ^ p1),
+        }
+    }
+
+    /// Whether `p0 <op> p1 == result` holds for every `p0` - then [Operator::undo] can't name a single one.
+    pub(crate) fn absorbs(&self, result: i64, p1: i64) -> bool {
+        matches!(self, Operator::Multiply) && p1 == 0 && result == 0
+    }
+
+    /// for non-negative operands the result is never smaller than the left operand
+    fn is_monotonic(&self) -> bool {
+        match self {
+            Operator::Add | Operator::Multiply | Operator::Concat { .. } => true,
+            Operator::Subtract | Operator::Xor => false,
+        }
+    }
+}
+
+/// Whether no intermediate result can be smaller than the one before - then a negative difference prunes a branch.
+pub(crate) fn is_monotonic(operators: &[Operator], operands: &[i64]) -> bool {
+    operators.iter().all(Operator::is_monotonic) && operands.iter().all(|n| *n >= 0)
+}
+
+/// smallest power of `base` that is greater than `n` - e.g. 100 for 42 and 10 for 0 (in base 10)
+fn next_power(n: i64, base: i64) -> Option<i64> {
+    if base < 2 {
+        return None;
+    }
+    let mut power = base;
+    while power <= n {
+        power = power.checked_mul(base)?;
+    }
+    Some(power)
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Operator::Add => write!(f, "+"),
+            Operator::Multiply => write!(f, "*"),
+            Operator::Concat { base: 10 } => write!(f, "||"),
+            Operator::Concat { base } => write!(f, "||{base}"),
+            Operator::Subtract => write!(f, "-"),
+            Operator::Xor => write!(f, "^"),
+        }
+    }
+}
+
+fn operator_parser(input: &str) -> IResult<&str, Operator> {
+    alt((
+        value(Operator::Add, char('+')),
+        value(Operator::Multiply, char('*')),
+        value(Operator::Subtract, char('-')),
+        value(Operator::Xor, char('^')),
+        map(
+            preceded(tag("||"), opt(verify(complete::i64, |base| *base >= 2))),
+            |maybe_base| Operator::Concat {
+                base: maybe_base.unwrap_or(10),
+            },
+        ),
+    ))(input)
+}
+
+/// Parses a space separated operator set, e.g. `+ * ||` or `- ^ ||2` - anything left over is an error.
+pub fn parse_operators(input: &str) -> IResult<&str, Vec<Operator>> {
+    all_consuming(separated_list1(space1, operator_parser))(input.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(Operator::CONCAT, 16, 6, Some(1))]
+    #[case(Operator::CONCAT, 156, 6, Some(15))]
+    #[case(Operator::CONCAT, 1010, 10, Some(10))]
+    #[case(Operator::CONCAT, 100, 0, Some(10))]
+    #[case(Operator::CONCAT, 6, 6, Some(0))]
+    #[case(Operator::CONCAT, 156, 7, None)]
+    // 0b101 || 0b11 = 0b10111
+    #[case(Operator::Concat { base: 2 }, 23, 3, Some(5))]
+    #[case(Operator::Subtract, 5, 7, Some(12))]
+    #[case(Operator::Xor, 6, 3, Some(5))]
+    #[case(Operator::Multiply, 7, 2, None)]
+    #[case(Operator::Multiply, 7, 0, None)]
+    // every p0 works, see test_absorbs
+    #[case(Operator::Multiply, 0, 0, None)]
+    // i64::MIN / -1 overflows
+    #[case(Operator::Multiply, i64::MIN, -1, None)]
+    #[case(Operator::Multiply, -8, -2, Some(4))]
+    fn test_undo(
+        #[case] operator: Operator,
+        #[case] result: i64,
+        #[case] p1: i64,
+        #[case] expected: Option<i64>,
+    ) {
+        assert_eq!(expected, operator.undo(result, p1, false));
+        if let Some(p0) = expected {
+            assert_eq!(Some(result), operator.perform(p0, p1));
+        }
+    }
+
+    #[rstest]
+    #[case(Operator::Multiply, 0, 0, true)]
+    #[case(Operator::Multiply, 7, 0, false)]
+    #[case(Operator::Multiply, 0, 7, false)]
+    #[case(Operator::Add, 0, 0, false)]
+    #[case(Operator::CONCAT, 0, 0, false)]
+    fn test_absorbs(
+        #[case] operator: Operator,
+        #[case] result: i64,
+        #[case] p1: i64,
+        #[case] expected: bool,
+    ) {
+        assert_eq!(expected, operator.absorbs(result, p1));
+        if expected {
+            assert!([-3, 0, 42]
+                .iter()
+                .all(|p0| operator.perform(*p0, p1) == Some(result)));
+        }
+    }
+
+    #[test]
+    fn test_parse_operators() {
+        let (remaining, operators) = parse_operators("+ * || - ^ ||16").unwrap();
+        assert_eq!(remaining, "");
+        assert_eq!(
+            operators,
+            vec![
+                Operator::Add,
+                Operator::Multiply,
+                Operator::CONCAT,
+                Operator::Subtract,
+                Operator::Xor,
+                Operator::Concat { base: 16 }
+            ]
+        );
+        assert_eq!(
+            operators
+                .iter()
+                .map(|o| o.to_string())
+                .collect::<Vec<_>>()
+                .join(" "),
+            "+ * || - ^ ||16"
+        );
+    }
+
+    #[rstest]
+    #[case("+ * /")]
+    #[case("||1")]
+    #[case("+ *x")]
+    #[case("")]
+    fn test_parse_operators_rejects_leftovers(#[case] input: &str) {
+        assert!(parse_operators(input).is_err());
+    }
+}
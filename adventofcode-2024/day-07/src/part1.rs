@@ -1,16 +1,8 @@
 use crate::Operator;
-use miette::miette;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, calibration_equations) =
-        crate::parse(input).map_err(|e| miette!("parse failed {}", e))?;
-
-    let result = crate::calc_result(
-        calibration_equations,
-        vec![Operator::Add, Operator::Multiply],
-    );
-    Ok(result.to_string())
+    crate::process_parameterized(input, &[Operator::Add, Operator::Multiply])
 }
 
 /*
@@ -33,7 +25,7 @@ Example 2:
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::get_all_combinations;
+    use crate::naive::get_all_combinations;
 
     #[test]
     fn test_process() -> miette::Result<()> {
@@ -1,16 +1,11 @@
 use crate::Operator;
-use miette::miette;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, calibration_equations) =
-        crate::parse(input).map_err(|e| miette!("parse failed {}", e))?;
-
-    let result = crate::calc_result(
-        calibration_equations,
-        vec![Operator::Add, Operator::Multiply, Operator::Concat],
-    );
-    Ok(result.to_string())
+    crate::process_parameterized(
+        input,
+        &[Operator::Add, Operator::Multiply, Operator::CONCAT],
+    )
 }
 
 #[cfg(test)]
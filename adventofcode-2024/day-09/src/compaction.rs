@@ -0,0 +1,188 @@
+use crate::disk_map::{CompactionStep, DiskMap, Span};
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompactionStrategy {
+    /// part 1: move single blocks from the end of the disk to the leftmost free block
+    BlockWise,
+    /// part 2: move whole files (highest id first) to the leftmost free span that fits them
+    WholeFile,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileSpan {
+    id: u64,
+    start: u64,
+    length: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FreeSpan {
+    start: u64,
+    length: u64,
+}
+
+/// Compacts the disk described by the dense disk map (e.g. `2333133121414131402`) and returns the filesystem checksum.
+/// Only the spans are tracked - the individual blocks are never materialised.
+pub fn compacted_checksum(disk_map: &str, strategy: CompactionStrategy) -> miette::Result<u64> {
+    let disk_map = DiskMap::parse(disk_map.trim())?;
+
+    Ok(compact(&disk_map, strategy, |_| {}))
+}
+
+/// Runs the compaction, reports every move to `on_step` and returns the checksum of the compacted disk.
+pub(crate) fn compact(
+    disk_map: &DiskMap,
+    strategy: CompactionStrategy,
+    mut on_step: impl FnMut(CompactionStep),
+) -> u64 {
+    let mut files = vec![];
+    let mut free_spans = vec![];
+    for (start, span) in disk_map.positioned_spans() {
+        match span {
+            Span::File { id, length } => files.push(FileSpan { id, start, length }),
+            Span::Free { length } if length > 0 => free_spans.push(FreeSpan { start, length }),
+            Span::Free { .. } => {}
+        }
+    }
+
+    match strategy {
+        CompactionStrategy::BlockWise => compact_block_wise(files, &free_spans, &mut on_step),
+        CompactionStrategy::WholeFile => compact_whole_files(files, &free_spans, &mut on_step),
+    }
+}
+
+/// `id * (start + (start + 1) + ... + (start + length - 1))`
+pub(crate) fn checksum_of(id: u64, start: u64, length: u64) -> u64 {
+    id * (length * start + length * length.saturating_sub(1) / 2)
+}
+
+/// Fills the free spans from the left with the blocks of the files from the right.
+/// Whatever is left of a file stays at its original position.
+fn compact_block_wise(
+    mut files: Vec<FileSpan>,
+    free_spans: &[FreeSpan],
+    on_step: &mut impl FnMut(CompactionStep),
+) -> u64 {
+    let mut checksum = 0;
+    let mut last_file = files.len();
+
+    for free_span in free_spans {
+        let mut position = free_span.start;
+        let end = free_span.start + free_span.length;
+
+        while position < end {
+            let Some(file) = files[..last_file].last_mut() else {
+                break;
+            };
+            if file.start + file.length <= position {
+                break;
+            }
+
+            let moved = (end - position).min(file.length);
+            // one step per block, the last block of the file goes first
+            for offset in 0..moved {
+                on_step(CompactionStep {
+                    file_id: file.id,
+                    from: file.start + file.length - 1 - offset,
+                    to: position + offset,
+                    length: 1,
+                });
+            }
+            checksum += checksum_of(file.id, position, moved);
+            position += moved;
+            file.length -= moved;
+            if file.length == 0 {
+                last_file -= 1;
+            }
+        }
+    }
+
+    checksum
+        + files
+            .iter()
+            .map(|file| checksum_of(file.id, file.start, file.length))
+            .sum::<u64>()
+}
+
+/// One min-heap of start positions per span length (1-9 for the dense format).
+/// The leftmost span that fits a file is the smallest top of all heaps with a length >= the file length,
+/// so every file moves in `O(log n)`.
+fn compact_whole_files(
+    files: Vec<FileSpan>,
+    free_spans: &[FreeSpan],
+    on_step: &mut impl FnMut(CompactionStep),
+) -> u64 {
+    let max_length = free_spans
+        .iter()
+        .map(|free_span| free_span.length)
+        .max()
+        .unwrap_or(0);
+    let mut free_by_length: Vec<BinaryHeap<Reverse<u64>>> =
+        vec![BinaryHeap::new(); max_length as usize + 1];
+    for free_span in free_spans {
+        free_by_length[free_span.length as usize].push(Reverse(free_span.start));
+    }
+
+    files
+        .iter()
+        .rev()
+        .filter(|file| file.length > 0)
+        .map(|file| {
+            let target = (file.length as usize..free_by_length.len())
+                .filter_map(|length| {
+                    free_by_length[length]
+                        .peek()
+                        .map(|Reverse(start)| (*start, length))
+                })
+                .filter(|(start, _)| *start < file.start)
+                .min();
+
+            let Some((start, length)) = target else {
+                return checksum_of(file.id, file.start, file.length);
+            };
+
+            free_by_length[length].pop();
+            let leftover = length - file.length as usize;
+            if leftover > 0 {
+                free_by_length[leftover].push(Reverse(start + file.length));
+            }
+            on_step(CompactionStep {
+                file_id: file.id,
+                from: file.start,
+                to: start,
+                length: file.length,
+            });
+            // the space freed at the old position is never needed again: all remaining files are further left
+            checksum_of(file.id, start, file.length)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("2333133121414131402", CompactionStrategy::BlockWise, 1928)]
+    #[case("2333133121414131402", CompactionStrategy::WholeFile, 2858)]
+    // 0..111....22222 -> 022111222......
+    #[case("12345", CompactionStrategy::BlockWise, 60)]
+    #[case("12345", CompactionStrategy::WholeFile, 132)]
+    #[case("1", CompactionStrategy::BlockWise, 0)]
+    #[case("909", CompactionStrategy::WholeFile, 117)]
+    fn test_compacted_checksum(
+        #[case] disk_map: &str,
+        #[case] strategy: CompactionStrategy,
+        #[case] expected: u64,
+    ) {
+        assert_eq!(expected, compacted_checksum(disk_map, strategy).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_disk_map() {
+        assert!(compacted_checksum("12a4", CompactionStrategy::WholeFile).is_err());
+    }
+}
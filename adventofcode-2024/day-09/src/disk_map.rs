@@ -0,0 +1,326 @@
+use crate::compaction::{compact, CompactionStrategy};
+use miette::miette;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Span {
+    File { id: u64, length: u64 },
+    Free { length: u64 },
+}
+
+impl Span {
+    pub fn length(&self) -> u64 {
+        match self {
+            Span::File { length, .. } | Span::Free { length } => *length,
+        }
+    }
+
+    fn with_length(&self, length: u64) -> Span {
+        match self {
+            Span::File { id, .. } => Span::File { id: *id, length },
+            Span::Free { .. } => Span::Free { length },
+        }
+    }
+
+    fn same_content(&self, other: &Span) -> bool {
+        match (self, other) {
+            (Span::File { id, .. }, Span::File { id: other_id, .. }) => id == other_id,
+            (Span::Free { .. }, Span::Free { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// `length` blocks of a file moved from block position `from` to `to` - one event of a compaction trace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CompactionStep {
+    pub file_id: u64,
+    pub from: u64,
+    pub to: u64,
+    pub length: u64,
+}
+
+/// The disk as a sequence of file and free spans.
+/// A freshly parsed map keeps every span of the dense format - including the ones with length 0 - so it serialises back to the same string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiskMap {
+    spans: Vec<Span>,
+}
+
+impl DiskMap {
+    /// Parses the dense format, e.g. `12345` is `0..111....22222`
+    pub fn parse(dense: &str) -> miette::Result<Self> {
+        let spans = dense
+            .char_indices()
+            .map(|(idx, char)| {
+                let length = char
+                    .to_digit(10)
+                    .ok_or_else(|| miette!("invalid length '{char}' at position {idx}"))?
+                    as u64;
+
+                Ok(if idx % 2 == 0 {
+                    Span::File {
+                        id: idx as u64 / 2,
+                        length,
+                    }
+                } else {
+                    Span::Free { length }
+                })
+            })
+            .collect::<miette::Result<Vec<_>>>()?;
+
+        Ok(DiskMap { spans })
+    }
+
+    /// Serialises back to the dense format.
+    /// Fails if the disk can't be expressed that way anymore - e.g. after compaction the file ids aren't in order.
+    pub fn to_dense(&self) -> miette::Result<String> {
+        let mut dense = String::new();
+        let mut next_id = 0;
+        let mut expect_file = true;
+
+        for span in &self.spans {
+            let length = u32::try_from(span.length())
+                .ok()
+                .and_then(|length| char::from_digit(length, 10))
+                .ok_or_else(|| miette!("span {span:?} is too long for the dense format"))?;
+
+            match span {
+                Span::File { id, .. } => {
+                    if !expect_file {
+                        // two adjacent files are separated by an empty free span
+                        dense.push('0');
+                    }
+                    if *id != next_id {
+                        return Err(miette!("expected file {next_id}, found file {id}"));
+                    }
+                    next_id += 1;
+                }
+                Span::Free { .. } => {
+                    if expect_file {
+                        return Err(miette!(
+                            "expected file {next_id}, found free space at position {}",
+                            dense.len()
+                        ));
+                    }
+                }
+            }
+            dense.push(length);
+            expect_file = !expect_file;
+        }
+
+        Ok(dense)
+    }
+
+    pub fn spans(&self) -> &[Span] {
+        &self.spans
+    }
+
+    /// the spans together with the position of their first block
+    pub fn positioned_spans(&self) -> impl Iterator<Item = (u64, Span)> + '_ {
+        self.spans.iter().scan(0, |position, span| {
+            let start = *position;
+            *position += span.length();
+            Some((start, *span))
+        })
+    }
+
+    /// The block view from the puzzle text, e.g. `00...111...2...`
+    /// Every block shows the complete id - only unambiguous if all ids are < 10.
+    pub fn render_blocks(&self) -> String {
+        self.spans
+            .iter()
+            .map(|span| match span {
+                Span::File { id, length } => id.to_string().repeat(*length as usize),
+                Span::Free { length } => ".".repeat(*length as usize),
+            })
+            .collect()
+    }
+
+    pub fn checksum(&self) -> u64 {
+        self.positioned_spans()
+            .map(|(start, span)| match span {
+                Span::File { id, length } => crate::compaction::checksum_of(id, start, length),
+                Span::Free { .. } => 0,
+            })
+            .sum()
+    }
+
+    /// Every move the compaction performs, in order. Block-wise compaction moves a single block per step.
+    pub fn compaction_steps(&self, strategy: CompactionStrategy) -> Vec<CompactionStep> {
+        let mut steps = vec![];
+        compact(self, strategy, |step| steps.push(step));
+        steps
+    }
+
+    /// The disk after all compaction steps.
+    pub fn compacted(&self, strategy: CompactionStrategy) -> DiskMap {
+        let mut disk_map = self.clone();
+        for step in self.compaction_steps(strategy) {
+            disk_map.apply(&step);
+        }
+        disk_map
+    }
+
+    /// Moves the blocks of a [CompactionStep]. Adjacent spans with the same content are merged afterwards.
+    pub fn apply(&mut self, step: &CompactionStep) {
+        self.replace_range(
+            step.from,
+            step.length,
+            Span::Free {
+                length: step.length,
+            },
+        );
+        self.replace_range(
+            step.to,
+            step.length,
+            Span::File {
+                id: step.file_id,
+                length: step.length,
+            },
+        );
+        self.normalize();
+    }
+
+    /// Replaces the blocks `start..start + length` with `span`, splitting the spans at the borders.
+    fn replace_range(&mut self, start: u64, length: u64, span: Span) {
+        let first = self.split_at(start);
+        let end = self.split_at(start + length);
+        self.spans.splice(first..end, [span]);
+    }
+
+    /// Makes sure a span starts at `position` and returns its index.
+    fn split_at(&mut self, position: u64) -> usize {
+        let mut span_start = 0;
+        for idx in 0..self.spans.len() {
+            let span = self.spans[idx];
+            if span_start == position {
+                return idx;
+            }
+            if position < span_start + span.length() {
+                let head = span.with_length(position - span_start);
+                let tail = span.with_length(span_start + span.length() - position);
+                self.spans.splice(idx..=idx, [head, tail]);
+                return idx + 1;
+            }
+            span_start += span.length();
+        }
+        self.spans.len()
+    }
+
+    fn normalize(&mut self) {
+        let mut spans: Vec<Span> = Vec::with_capacity(self.spans.len());
+        for span in self.spans.iter().filter(|span| span.length() > 0) {
+            match spans.last_mut() {
+                Some(last) if last.same_content(span) => {
+                    *last = last.with_length(last.length() + span.length());
+                }
+                _ => spans.push(*span),
+            }
+        }
+        self.spans = spans;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const EXAMPLE: &str = "2333133121414131402";
+
+    #[rstest]
+    #[case(EXAMPLE)]
+    #[case("12345")]
+    #[case("12031")]
+    #[case("1210")]
+    fn test_dense_round_trip(#[case] dense: &str) -> miette::Result<()> {
+        assert_eq!(dense, DiskMap::parse(dense)?.to_dense()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_render_blocks() -> miette::Result<()> {
+        assert_eq!(
+            "00...111...2...333.44.5555.6666.777.888899",
+            DiskMap::parse(EXAMPLE)?.render_blocks()
+        );
+        assert_eq!("0..111....22222", DiskMap::parse("12345")?.render_blocks());
+        Ok(())
+    }
+
+    #[test]
+    fn test_block_wise_trace() -> miette::Result<()> {
+        let expected = r#"
+00...111...2...333.44.5555.6666.777.888899
+009..111...2...333.44.5555.6666.777.88889.
+0099.111...2...333.44.5555.6666.777.8888..
+00998111...2...333.44.5555.6666.777.888...
+009981118..2...333.44.5555.6666.777.88....
+0099811188.2...333.44.5555.6666.777.8.....
+009981118882...333.44.5555.6666.777.......
+0099811188827..333.44.5555.6666.77........
+00998111888277.333.44.5555.6666.7.........
+009981118882777333.44.5555.6666...........
+009981118882777333644.5555.666............
+00998111888277733364465555.66.............
+0099811188827773336446555566.............."#
+            .trim();
+
+        let mut disk_map = DiskMap::parse(EXAMPLE)?;
+        let mut states = vec![disk_map.render_blocks()];
+        for step in disk_map.compaction_steps(CompactionStrategy::BlockWise) {
+            disk_map.apply(&step);
+            states.push(disk_map.render_blocks());
+        }
+
+        assert_eq!(expected, states.join("\n"));
+        assert_eq!(1928, disk_map.checksum());
+        assert!(disk_map.to_dense().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_whole_file_trace() -> miette::Result<()> {
+        let expected = r#"
+00...111...2...333.44.5555.6666.777.888899
+0099.111...2...333.44.5555.6666.777.8888..
+0099.1117772...333.44.5555.6666.....8888..
+0099.111777244.333....5555.6666.....8888..
+00992111777.44.333....5555.6666.....8888.."#
+            .trim();
+
+        let disk_map = DiskMap::parse(EXAMPLE)?;
+        let steps = disk_map.compaction_steps(CompactionStrategy::WholeFile);
+        assert_eq!(
+            steps[0],
+            CompactionStep {
+                file_id: 9,
+                from: 40,
+                to: 2,
+                length: 2
+            }
+        );
+
+        let states = steps
+            .iter()
+            .scan(disk_map.clone(), |current, step| {
+                current.apply(step);
+                Some(current.render_blocks())
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            expected,
+            [disk_map.render_blocks()]
+                .into_iter()
+                .chain(states)
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+        assert_eq!(
+            2858,
+            disk_map.compacted(CompactionStrategy::WholeFile).checksum()
+        );
+        Ok(())
+    }
+}
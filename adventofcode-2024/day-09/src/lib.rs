@@ -1,2 +1,4 @@
+pub mod compaction;
+pub mod disk_map;
 pub mod part1;
 pub mod part2;
@@ -1,89 +1,104 @@
-use itertools::{repeat_n, Itertools};
+use crate::compaction::{compacted_checksum, CompactionStrategy};
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let mut disk_blocks = parse_disk_blocks(input.trim());
-    let compaction_result = compact_until_finished(&mut disk_blocks);
-    assert!(compaction_result);
-
-    let result = compute_checksum(&disk_blocks);
+    let result = compacted_checksum(input, CompactionStrategy::BlockWise)?;
 
     Ok(result.to_string())
 }
 
-#[derive(PartialOrd, PartialEq)]
-enum CompactionResult {
-    Finished,
-    OneStepDone,
-    Error,
-}
+/// The original implementation that materialises every block - kept as reference for the compaction engine.
+#[cfg(test)]
+mod reference {
+    use itertools::{repeat_n, Itertools};
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub(crate) enum CompactionResult {
+        Finished,
+        OneStepDone,
+        /// there is no empty or no used block on the disk
+        NothingToCompact,
+    }
 
-fn compact_one_block(disk_blocks: &mut Vec<Option<u16>>) -> CompactionResult {
-    if let Some(first_empty_idx) = disk_blocks.iter().position(|b| b.is_none()) {
-        if let Some(last_nonempty_idx) = disk_blocks.iter().rposition(|b| b.is_some()) {
-            if first_empty_idx > last_nonempty_idx {
-                return CompactionResult::Finished;
+    pub(crate) fn compact_one_block(disk_blocks: &mut [Option<u16>]) -> CompactionResult {
+        let first_empty_idx = disk_blocks.iter().position(|b| b.is_none());
+        let last_nonempty_idx = disk_blocks.iter().rposition(|b| b.is_some());
+        match (first_empty_idx, last_nonempty_idx) {
+            (Some(first_empty_idx), Some(last_nonempty_idx)) => {
+                if first_empty_idx > last_nonempty_idx {
+                    return CompactionResult::Finished;
+                }
+                disk_blocks.swap(first_empty_idx, last_nonempty_idx);
+                CompactionResult::OneStepDone
             }
-            disk_blocks.swap(first_empty_idx, last_nonempty_idx);
-            CompactionResult::OneStepDone
-        } else {
-            CompactionResult::Error
+            _ => CompactionResult::NothingToCompact,
         }
-    } else {
-        CompactionResult::Error
     }
-}
-
-fn compute_checksum(disk_blocks: &Vec<Option<u16>>) -> usize {
-    disk_blocks
-        .iter()
-        .enumerate()
-        .fold(0usize, |acc, (idx, block_id)| {
-            acc + idx * block_id.unwrap_or(0) as usize
-        })
-}
 
-fn compact_until_finished(disk_blocks: &mut Vec<Option<u16>>) -> bool {
-    loop {
-        let result = compact_one_block(disk_blocks);
+    pub(crate) fn compute_checksum(disk_blocks: &[Option<u16>]) -> usize {
+        disk_blocks
+            .iter()
+            .enumerate()
+            .fold(0usize, |acc, (idx, block_id)| {
+                acc + idx * block_id.unwrap_or(0) as usize
+            })
+    }
 
-        match result {
-            CompactionResult::Finished => {
-                return true;
-            }
-            CompactionResult::OneStepDone => {
-                continue;
-            }
-            CompactionResult::Error => {
-                return false;
+    /// `false` if there was nothing to compact
+    pub(crate) fn compact_until_finished(disk_blocks: &mut [Option<u16>]) -> bool {
+        loop {
+            match compact_one_block(disk_blocks) {
+                CompactionResult::Finished => return true,
+                CompactionResult::OneStepDone => continue,
+                CompactionResult::NothingToCompact => return false,
             }
         }
     }
-}
 
-/// Parses the disk blocks. -1 indicate an empty block. Might be premature optimization, but I think it's faster to use a number and not an enum / Option due to overhead
-fn parse_disk_blocks(input: &str) -> Vec<Option<u16>> {
-    let disk_blocks = input
-        .char_indices()
-        .flat_map(|(idx, char)| {
-            let idx = idx as u16;
-            let id: Option<u16> = if idx % 2 != 0 { None } else { Some(idx / 2) };
-            let qty = match format!("{char}").parse::<i16>() {
-                Ok(x) => x,
-                Err(err) => {
-                    panic!("error parsing char '{char}': {}", err)
-                }
-            };
-            repeat_n(id, qty as usize)
-        })
-        .collect_vec();
+    /// One entry per block, `None` for an empty block.
+    pub(crate) fn parse_disk_blocks(input: &str) -> Vec<Option<u16>> {
+        input
+            .char_indices()
+            .flat_map(|(idx, char)| {
+                let idx = idx as u16;
+                let id = idx.is_multiple_of(2).then_some(idx / 2);
+                let qty = char.to_digit(10).expect("disk map only contains digits");
+                repeat_n(id, qty as usize)
+            })
+            .collect_vec()
+    }
 
-    disk_blocks
+    /// only makes sense to render disk blocks where max id < 10
+    /// good enough for the small test examples
+    pub(crate) fn render_disk_blocks(blocks: &[Option<u16>]) -> String {
+        blocks
+            .iter()
+            .map(|id| id.map(|id| id.to_string()).unwrap_or(".".to_string()))
+            .join("")
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::reference::*;
     use super::*;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("2333133121414131402")]
+    #[case("12345")]
+    #[case("1010101010101010101027")]
+    #[case("9953877292941")]
+    #[case("354631466260")]
+    #[case("171010402")]
+    fn test_matches_reference_implementation(#[case] input: &str) -> miette::Result<()> {
+        let mut disk_blocks = parse_disk_blocks(input);
+        assert!(compact_until_finished(&mut disk_blocks));
+
+        assert_eq!(compute_checksum(&disk_blocks).to_string(), process(input)?);
+        Ok(())
+    }
 
     #[test]
     fn test_process() -> miette::Result<()> {
@@ -133,7 +148,6 @@ mod tests {
         "#
         .trim();
         let disk_blocks = parse_disk_blocks(input);
-        dbg!(&disk_blocks);
         assert_eq!(
             "00...111...2...333.44.5555.6666.777.888899",
             render_disk_blocks(&disk_blocks)
@@ -193,13 +207,4 @@ mod tests {
         // 0..111....22222
         //
     }
-
-    /// only makes sense to render disk blocks where max id < 10
-    /// good enough for the small test examples
-    fn render_disk_blocks(blocks: &Vec<Option<u16>>) -> String {
-        blocks
-            .iter()
-            .map(|id| id.map(|id| id.to_string()).unwrap_or(".".to_string()))
-            .join("")
-    }
 }
@@ -1,295 +1,210 @@
-use itertools::{repeat_n, Itertools};
-use tracing::debug;
+use crate::compaction::{compacted_checksum, CompactionStrategy};
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let mut disk_chunks = parse_disk_chunks(input.trim());
-    compact_until_finished(&mut disk_chunks);
-
-    let result = compute_checksum(&disk_chunks);
+    let result = compacted_checksum(input, CompactionStrategy::WholeFile)?;
 
     Ok(result.to_string())
 }
 
-#[derive(PartialOrd, PartialEq)]
-enum CompactionResult {
-    Finished,
-    OneStepDone { id: u16 },
-    Error,
-    NoOp,
-}
+/// The original implementation that moves whole chunks around - kept as reference for the compaction engine.
+#[cfg(test)]
+mod reference {
+    use itertools::{repeat_n, Itertools};
+
+    #[derive(Debug, PartialEq, Eq)]
+    pub(crate) enum CompactionResult {
+        Finished,
+        /// the file with `id` was moved or can't be moved
+        OneStepDone {
+            id: u16,
+        },
+    }
 
-fn compact_until_finished(disk_chunks: &mut Vec<Chunk>) {
-    let mut max_id = None;
+    #[derive(Debug, Clone, Copy)]
+    pub(crate) enum Chunk {
+        File { id: u16, length: u16 },
+        Empty { length: u16 },
+    }
 
-    loop {
-        let result = compact_one_chunk(disk_chunks, max_id);
+    pub(crate) fn compact_until_finished(disk_chunks: &mut Vec<Chunk>) {
+        let mut max_id = None;
 
-        match result {
-            CompactionResult::Error => {
-                panic!("Should not happen")
-            }
-            CompactionResult::Finished => {
+        while let CompactionResult::OneStepDone { id } = compact_one_chunk(disk_chunks, max_id) {
+            if id == 0 {
                 break;
             }
-            CompactionResult::OneStepDone { id } => {
-                if id > 0 {
-                    max_id = Some(id - 1)
-                } else {
-                    break;
-                }
-            }
-            CompactionResult::NoOp => {}
+            max_id = Some(id - 1)
         }
     }
-}
 
-fn compact_one_chunk(disk_chunks: &mut Vec<Chunk>, max_id: Option<u16>) -> CompactionResult {
-    debug!("compact_one_chunk start");
-    debug!("{}", render_disk_chunks(disk_chunks));
-    if let Some(file_idx) = disk_chunks.iter().rposition(|chunk| match chunk {
-        Chunk::File { id, .. } if *id <= max_id.unwrap_or(u16::MAX) => true,
-        _ => false,
-    }) {
-        let file_id = match disk_chunks[file_idx] {
-            Chunk::File { id, .. } => id,
-            Chunk::Empty { .. } => panic!("Should find file_id"),
+    /// Moves the last file with an id of at most `max_id` to the first empty chunk that fits it.
+    pub(crate) fn compact_one_chunk(
+        disk_chunks: &mut Vec<Chunk>,
+        max_id: Option<u16>,
+    ) -> CompactionResult {
+        let max_id = max_id.unwrap_or(u16::MAX);
+        let Some((file_idx, id, file_length)) =
+            disk_chunks
+                .iter()
+                .enumerate()
+                .rev()
+                .find_map(|(idx, chunk)| match chunk {
+                    Chunk::File { id, length } if *id <= max_id => Some((idx, *id, *length)),
+                    _ => None,
+                })
+        else {
+            return CompactionResult::Finished;
         };
 
-        debug!(
-            "last valid file is at position: {file_idx}: {:?}",
-            disk_chunks[file_idx]
-        );
-        let required_length = match disk_chunks[file_idx] {
-            Chunk::File { length, .. } => length,
-            _ => panic!("should be a file"),
+        let Some((empty_idx, empty_length)) =
+            disk_chunks
+                .iter()
+                .enumerate()
+                .find_map(|(idx, chunk)| match chunk {
+                    Chunk::Empty { length } if *length >= file_length => Some((idx, *length)),
+                    _ => None,
+                })
+        else {
+            // no empty chunk is large enough
+            return CompactionResult::OneStepDone { id };
         };
-
-        if let Some(empty_idx) = disk_chunks.iter().position(|chunk| match chunk {
-            Chunk::Empty { length, .. } if *length >= required_length => true,
-            _ => false,
-        }) {
-            if empty_idx > file_idx {
-                debug!("empty_idx {empty_idx} is not < file_idx {file_idx}");
-                return CompactionResult::OneStepDone { id: file_id };
-            }
-            debug!(
-                "first matching empty block at position: {empty_idx}: {:?}",
-                disk_chunks[empty_idx]
-            );
-            match (disk_chunks[file_idx], disk_chunks[empty_idx]) {
-                (
-                    Chunk::File {
-                        id,
-                        length: file_length,
-                    },
-                    Chunk::Empty {
-                        length: empty_length,
-                    },
-                ) => {
-                    let empty_space_merge_candidate_idx = if file_length == empty_length {
-                        debug!("swapped equally sized (length {file_length} empty chunk with file chunk");
-                        disk_chunks.swap(empty_idx, file_idx);
-                        empty_idx
-                    } else {
-                        disk_chunks.swap(empty_idx, file_idx);
-                        // the file was smaller than the empty space - insert a new empty space chunk after the file
-                        let leftover_length = empty_length - file_length;
-                        disk_chunks.insert(
-                            empty_idx + 1,
-                            Chunk::Empty {
-                                length: leftover_length,
-                            },
-                        );
-                        // now the empty space (that has moved to the back) is too large. It needs to be trimmed to file_length.
-                        debug!("swapped file (length {file_length} with empty chunk (length {empty_length}) file chunk and inserted new empty chunk of length {leftover_length} after the file");
-                        disk_chunks[file_idx + 1].set_length(file_length);
-                        debug!("set length of empty space at the end to {file_length}");
-                        file_idx + 1
-                    };
-                    merge_empty_space(disk_chunks, empty_space_merge_candidate_idx);
-                    CompactionResult::OneStepDone { id }
-                }
-                _ => panic!("should not happen"),
-            }
-        } else {
-            debug!("no empty slot of length {required_length} found");
-            //TODO: Find better way to extract id here that avoids the pattern match - ideally something like r_find_some
-            match disk_chunks[file_idx] {
-                Chunk::File { id, .. } => CompactionResult::OneStepDone { id },
-                Chunk::Empty { .. } => CompactionResult::Error,
-            }
+        if empty_idx > file_idx {
+            return CompactionResult::OneStepDone { id };
         }
-    } else {
-        debug!("no file left to process");
-        CompactionResult::Finished
-    }
-}
 
-fn merge_empty_space(disk_chunks: &mut Vec<Chunk>, empty_space_merge_candidate_idx: usize) {
-    debug!("checking if empty space at idx {empty_space_merge_candidate_idx} can be merged with surrounding empty space");
-    debug!("{}", render_disk_chunks(disk_chunks));
-    match (
-        disk_chunks.get(empty_space_merge_candidate_idx - 1),
-        disk_chunks.get(empty_space_merge_candidate_idx),
-        disk_chunks.get(empty_space_merge_candidate_idx + 1),
-    ) {
-        (
-            Some(Chunk::Empty { length: length_1 }),
-            Some(Chunk::Empty { length: length_2 }),
-            Some(Chunk::Empty { length: length_3 }),
-        ) => {
-            let total_length = length_1 + length_2 + length_3;
-            debug!("found 3 empty chunks of lengths ({length_1}, {length_2}, {length_3}) that can be merged to one chunk of length {total_length}");
-            disk_chunks[empty_space_merge_candidate_idx - 1] = Chunk::Empty {
-                length: total_length,
-            };
-            disk_chunks.remove(empty_space_merge_candidate_idx + 1);
-            disk_chunks.remove(empty_space_merge_candidate_idx);
-        }
-        (Some(Chunk::Empty { length: length_1 }), Some(Chunk::Empty { length: length_2 }), _) => {
-            let total_length = length_1 + length_2;
-            debug!("found 2 empty chunks of lengths ({length_1}, {length_2}) that can be merged to one chunk of length {total_length}");
-            disk_chunks[empty_space_merge_candidate_idx - 1] = Chunk::Empty {
-                length: total_length,
-            };
-            disk_chunks.remove(empty_space_merge_candidate_idx);
-        }
-        (_, Some(Chunk::Empty { length: length_1 }), Some(Chunk::Empty { length: length_2 })) => {
-            let total_length = length_1 + length_2;
-            debug!("found 2 empty chunks of lengths ({length_1}, {length_2}) that can be merged to one chunk of length {total_length}");
-            disk_chunks[empty_space_merge_candidate_idx] = Chunk::Empty {
-                length: total_length,
+        disk_chunks.swap(empty_idx, file_idx);
+        let empty_space_merge_candidate_idx = if file_length == empty_length {
+            empty_idx
+        } else {
+            // the file was smaller than the empty space - insert a new empty space chunk after the file
+            disk_chunks.insert(
+                empty_idx + 1,
+                Chunk::Empty {
+                    length: empty_length - file_length,
+                },
+            );
+            // now the empty space (that has moved to the back) is too large. It needs to be trimmed to file_length.
+            disk_chunks[file_idx + 1] = Chunk::Empty {
+                length: file_length,
             };
-            disk_chunks.remove(empty_space_merge_candidate_idx + 1);
-        }
-        surroundings => {
-            debug!("no consecutive empty chunks found {surroundings:?}");
-        }
-    };
-    debug!("{}", render_disk_chunks(disk_chunks));
-}
+            file_idx + 1
+        };
+        merge_empty_space(disk_chunks, empty_space_merge_candidate_idx);
+        CompactionResult::OneStepDone { id }
+    }
 
-fn compute_checksum(disk_blocks: &[Chunk]) -> usize {
-    disk_blocks
-        .iter()
-        .fold((0usize, 0u32), |(acc, idx), chunk| match chunk {
-            Chunk::File { id, length } => {
-                let checksum_of_this_file: u32 = (idx..(idx + *length as u32))
-                    .map(|idx| idx * *id as u32)
-                    .sum();
-                (acc + checksum_of_this_file as usize, idx + *length as u32)
+    fn merge_empty_space(disk_chunks: &mut Vec<Chunk>, empty_space_merge_candidate_idx: usize) {
+        let idx = empty_space_merge_candidate_idx;
+        match (
+            idx.checked_sub(1)
+                .and_then(|previous| disk_chunks.get(previous)),
+            disk_chunks.get(idx),
+            disk_chunks.get(idx + 1),
+        ) {
+            (
+                Some(Chunk::Empty { length: length_1 }),
+                Some(Chunk::Empty { length: length_2 }),
+                Some(Chunk::Empty { length: length_3 }),
+            ) => {
+                disk_chunks[idx - 1] = Chunk::Empty {
+                    length: length_1 + length_2 + length_3,
+                };
+                disk_chunks.remove(idx + 1);
+                disk_chunks.remove(idx);
             }
-            Chunk::Empty { length } => (acc, idx + *length as u32),
-        })
-        .0
-}
-
-#[derive(Debug, Clone, Copy)]
-enum Chunk {
-    File { id: u16, length: u16 },
-    Empty { length: u16 },
-}
-
-impl Chunk {
-    pub(crate) fn set_length(&mut self, new_length: u16) {
-        *self = match *self {
-            Chunk::File { id, .. } => Chunk::File {
-                id,
-                length: new_length,
-            },
-            Chunk::Empty { .. } => Chunk::Empty { length: new_length },
+            (
+                Some(Chunk::Empty { length: length_1 }),
+                Some(Chunk::Empty { length: length_2 }),
+                _,
+            ) => {
+                disk_chunks[idx - 1] = Chunk::Empty {
+                    length: length_1 + length_2,
+                };
+                disk_chunks.remove(idx);
+            }
+            (
+                _,
+                Some(Chunk::Empty { length: length_1 }),
+                Some(Chunk::Empty { length: length_2 }),
+            ) => {
+                disk_chunks[idx] = Chunk::Empty {
+                    length: length_1 + length_2,
+                };
+                disk_chunks.remove(idx + 1);
+            }
+            _ => {}
         };
     }
-}
-
-fn parse_disk_chunks(input: &str) -> Vec<Chunk> {
-    let disk_chunks = input
-        .char_indices()
-        .scan(0u16, |total, (idx, char)| {
-            let idx = idx as u16;
-            let length = match format!("{char}").parse::<u16>() {
-                Ok(x) => x,
-                Err(err) => {
-                    panic!("error parsing char '{char}': {}", err)
-                }
-            };
 
-            let chunk: Chunk = if idx % 2 != 0 {
-                Chunk::Empty { length }
-            } else {
-                Chunk::File {
-                    id: idx / 2,
-                    length,
+    pub(crate) fn compute_checksum(disk_chunks: &[Chunk]) -> usize {
+        disk_chunks
+            .iter()
+            .fold((0usize, 0u32), |(acc, idx), chunk| match chunk {
+                Chunk::File { id, length } => {
+                    let checksum_of_this_file: u32 = (idx..(idx + *length as u32))
+                        .map(|idx| idx * *id as u32)
+                        .sum();
+                    (acc + checksum_of_this_file as usize, idx + *length as u32)
                 }
-            };
-
-            *total += length;
-
-            Some(chunk)
-        })
-        .filter(|chunk| match chunk {
-            Chunk::File { length, .. } => *length > 0,
-            Chunk::Empty { length, .. } => *length > 0,
-        })
-        .collect_vec();
-
-    disk_chunks
-}
-
-fn render_disk_chunks(chunks: &[Chunk]) -> String {
-    chunks
-        .iter()
-        .flat_map(|chunk| match chunk {
-            Chunk::File { id, length } => repeat_n(id.to_string(), *length as usize),
-            Chunk::Empty { length } => repeat_n(".".to_string(), *length as usize),
-        })
-        .join("")
-}
+                Chunk::Empty { length } => (acc, idx + *length as u32),
+            })
+            .0
+    }
 
-/// Parses the disk blocks. -1 indicate an empty block. Might be premature optimization, but I think it's faster to use a number and not an enum / Option due to overhead
-fn parse_disk_blocks(input: &str) -> Vec<Option<u16>> {
-    let disk_blocks = input
-        .char_indices()
-        .flat_map(|(idx, char)| {
-            let idx = idx as u16;
-            let id: Option<u16> = if idx % 2 != 0 { None } else { Some(idx / 2) };
-            let qty = match format!("{char}").parse::<i16>() {
-                Ok(x) => x,
-                Err(err) => {
-                    panic!("error parsing char '{char}': {}", err)
+    /// One chunk per non-empty entry of the disk map.
+    pub(crate) fn parse_disk_chunks(input: &str) -> Vec<Chunk> {
+        input
+            .char_indices()
+            .map(|(idx, char)| {
+                let idx = idx as u16;
+                let length = char.to_digit(10).expect("disk map only contains digits") as u16;
+                if idx.is_multiple_of(2) {
+                    Chunk::File {
+                        id: idx / 2,
+                        length,
+                    }
+                } else {
+                    Chunk::Empty { length }
                 }
-            };
-            repeat_n(id, qty as usize)
-        })
-        .collect_vec();
+            })
+            .filter(|chunk| match chunk {
+                Chunk::File { length, .. } | Chunk::Empty { length } => *length > 0,
+            })
+            .collect_vec()
+    }
 
-    disk_blocks
+    /// only makes sense to render disk chunks where max id < 10
+    pub(crate) fn render_disk_chunks(chunks: &[Chunk]) -> String {
+        chunks
+            .iter()
+            .flat_map(|chunk| match chunk {
+                Chunk::File { id, length } => repeat_n(id.to_string(), *length as usize),
+                Chunk::Empty { length } => repeat_n(".".to_string(), *length as usize),
+            })
+            .join("")
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::reference::*;
     use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case("2333133121414131402")]
+    #[case("12345")]
+    #[case("1010101010101010101027")]
+    #[case("9953877292941")]
+    #[case("354631466260")]
+    #[case("171010402")]
+    fn test_matches_reference_implementation(#[case] input: &str) -> miette::Result<()> {
+        let mut disk_chunks = parse_disk_chunks(input);
+        compact_until_finished(&mut disk_chunks);
 
-    #[test]
-    fn test_parsing() -> miette::Result<()> {
-        let input = r#"
-2333133121414131402
-        "#
-        .trim();
-        let disk_blocks = parse_disk_blocks(input);
-        dbg!(&disk_blocks);
-        assert_eq!(
-            "00...111...2...333.44.5555.6666.777.888899",
-            render_disk_blocks(&disk_blocks)
-        );
+        assert_eq!(compute_checksum(&disk_chunks).to_string(), process(input)?);
         Ok(())
-
-        // example 1
-        // input: 12345
-        // disk layout of this input is:
-        // 0..111....22222
-        //
     }
 
     #[test]
@@ -300,11 +215,10 @@ mod tests {
         .trim();
         let chunks = parse_disk_chunks(input);
 
-        dbg!(&chunks);
-        // assert_eq!(
-        //     "00...111...2...333.44.5555.6666.777.888899",
-        //     render_disk_blocks(&disk_blocks)
-        // );
+        assert_eq!(
+            "00...111...2...333.44.5555.6666.777.888899",
+            render_disk_chunks(&chunks)
+        );
         Ok(())
 
         // example 1
@@ -362,16 +276,6 @@ mod tests {
         .trim();
         let mut disk_chunks = parse_disk_chunks(input);
 
-        let expected = r#"
-00...111...2...333.44.5555.6666.777.888899
-0099.111...2...333.44.5555.6666.777.8888..
-0099.1117772...333.44.5555.6666.....8888..
-0099.111777244.333....5555.6666.....8888..
-00992111777.44.333....5555.6666.....8888..
-        "#
-        .trim()
-        .to_string();
-
         compact_one_chunk(&mut disk_chunks, None);
         assert_eq!(
             "0099.111...2...333.44.5555.6666.777.8888..",
@@ -429,13 +333,4 @@ mod tests {
         // 0..111....22222
         //
     }
-
-    /// only makes sense to render disk blocks where max id < 10
-    /// good enough for the small test examples
-    fn render_disk_blocks(blocks: &Vec<Option<u16>>) -> String {
-        blocks
-            .iter()
-            .map(|id| id.map(|id| id.to_string()).unwrap_or(".".to_string()))
-            .join("")
-    }
 }
@@ -0,0 +1,227 @@
+use miette::miette;
+use std::collections::{HashMap, HashSet};
+
+/// What happens to a single stone when you blink.
+pub trait RuleSet {
+    /// The stones that replace `stone` - `None` if an engraving doesn't fit into an u64.
+    fn blink(&self, stone: u64) -> Option<Vec<u64>>;
+}
+
+impl<F> RuleSet for F
+where
+    F: Fn(u64) -> Option<Vec<u64>>,
+{
+    fn blink(&self, stone: u64) -> Option<Vec<u64>> {
+        self(stone)
+    }
+}
+
+/// The rules from the puzzle, applied in order:
+/// - `0` becomes `1`
+/// - a stone with an even number of digits is split into two stones, e.g. `1000` becomes `10` and `0`
+/// - every other stone is multiplied by 2024
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PuzzleRules;
+
+impl RuleSet for PuzzleRules {
+    fn blink(&self, stone: u64) -> Option<Vec<u64>> {
+        if stone == 0 {
+            return Some(vec![1]);
+        }
+
+        let digits = stone.ilog10() + 1;
+        if digits.is_multiple_of(2) {
+            let divisor = 10u64.pow(digits / 2);
+            Some(vec![stone / divisor, stone % divisor])
+        } else {
+            stone.checked_mul(2024).map(|product| vec![product])
+        }
+    }
+}
+
+/// Counts stones for any [RuleSet]. `count(stone, blinks)` is memoised across calls,
+/// so asking for more blinks or other stones reuses everything that has been computed before.
+pub struct StoneEngine<R> {
+    rules: R,
+    memo: HashMap<(u64, usize), u128>,
+}
+
+impl<R: RuleSet> StoneEngine<R> {
+    pub fn new(rules: R) -> Self {
+        StoneEngine {
+            rules,
+            memo: HashMap::new(),
+        }
+    }
+
+    fn blink_stone(&self, stone: u64) -> miette::Result<Vec<u64>> {
+        self.rules
+            .blink(stone)
+            .ok_or_else(|| miette!("engraving of the stone after {stone} doesn't fit into an u64"))
+    }
+
+    /// The stones after one blink, in order.
+    pub fn blink(&self, stones: &[u64]) -> miette::Result<Vec<u64>> {
+        let mut new_stones = Vec::with_capacity(stones.len() * 2);
+        for stone in stones {
+            new_stones.extend(self.blink_stone(*stone)?);
+        }
+        Ok(new_stones)
+    }
+
+    /// Number of stones that a single `stone` turns into after `blinks` blinks.
+    /// Fails instead of wrapping around if the number doesn't fit into an u128.
+    pub fn count(&mut self, stone: u64, blinks: usize) -> miette::Result<u128> {
+        // depth first with an explicit stack - thousands of blinks would overflow the call stack
+        let mut stack = vec![(stone, blinks)];
+
+        while let Some(&(stone, blinks)) = stack.last() {
+            if self.memo.contains_key(&(stone, blinks)) {
+                stack.pop();
+                continue;
+            }
+            if blinks == 0 {
+                self.memo.insert((stone, 0), 1);
+                stack.pop();
+                continue;
+            }
+
+            let children = self.blink_stone(stone)?;
+            let missing = children
+                .iter()
+                .filter(|child| !self.memo.contains_key(&(**child, blinks - 1)))
+                .map(|child| (*child, blinks - 1))
+                .collect::<Vec<_>>();
+
+            if missing.is_empty() {
+                let count = children.iter().try_fold(0u128, |acc, child| {
+                    acc.checked_add(self.memo[&(*child, blinks - 1)])
+                });
+                let count = count.ok_or_else(|| {
+                    miette!(
+                        "number of stones after {blinks} blinks of stone {stone} overflows an u128"
+                    )
+                })?;
+                self.memo.insert((stone, blinks), count);
+                stack.pop();
+            } else {
+                stack.extend(missing);
+            }
+        }
+
+        Ok(self.memo[&(stone, blinks)])
+    }
+
+    /// Number of stones after `blinks` blinks for the whole row.
+    pub fn count_all(&mut self, stones: &[u64], blinks: usize) -> miette::Result<u128> {
+        stones.iter().try_fold(0u128, |acc, stone| {
+            acc.checked_add(self.count(*stone, blinks)?)
+                .ok_or_else(|| miette!("number of stones after {blinks} blinks overflows an u128"))
+        })
+    }
+
+    /// The set of different engravings after `blinks` blinks.
+    /// Grows much slower than the number of stones - only a few thousand for the puzzle rules.
+    pub fn distinct_stones(&self, stones: &[u64], blinks: usize) -> miette::Result<HashSet<u64>> {
+        let mut distinct: HashSet<u64> = stones.iter().copied().collect();
+        for _ in 0..blinks {
+            let mut next = HashSet::with_capacity(distinct.len());
+            for stone in distinct {
+                next.extend(self.blink_stone(stone)?);
+            }
+            distinct = next;
+        }
+        Ok(distinct)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[test]
+    fn test_blink_sequence() -> miette::Result<()> {
+        let engine = StoneEngine::new(PuzzleRules);
+
+        let mut stones = vec![125, 17];
+        let mut lines = vec![];
+        for _ in 0..6 {
+            stones = engine.blink(&stones)?;
+            lines.push(stones.iter().join(" "));
+        }
+
+        let expected = r#"
+253000 1 7
+253 0 2024 14168
+512072 1 20 24 28676032
+512 72 2024 2 0 2 4 2867 6032
+1036288 7 2 20 24 4048 1 4048 8096 28 67 60 32
+2097446912 14168 4048 2 0 2 4 40 48 2024 40 48 80 96 2 8 6 7 6 0 3 2
+        "#
+        .trim();
+        assert_eq!(expected, lines.join("\n"));
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(&[125, 17], 6, 22)]
+    #[case(&[125, 17], 25, 55312)]
+    #[case(&[0, 1, 10, 99, 999], 1, 7)]
+    fn test_count_all(
+        #[case] stones: &[u64],
+        #[case] blinks: usize,
+        #[case] expected: u128,
+    ) -> miette::Result<()> {
+        let mut engine = StoneEngine::new(PuzzleRules);
+        assert_eq!(expected, engine.count_all(stones, blinks)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_count_matches_expansion() -> miette::Result<()> {
+        let mut engine = StoneEngine::new(PuzzleRules);
+        let mut stones = vec![0, 7, 2024];
+        for blinks in 0..15 {
+            assert_eq!(
+                stones.len() as u128,
+                engine.count_all(&[0, 7, 2024], blinks)?
+            );
+            stones = engine.blink(&stones)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_large_blink_counts() -> miette::Result<()> {
+        let mut engine = StoneEngine::new(PuzzleRules);
+        // still fits into an u128
+        assert!(engine.count_all(&[125, 17], 150)? > u64::MAX as u128);
+        // doesn't fit - but doesn't blow the stack or wrap around either
+        assert!(engine.count_all(&[125, 17], 10_000).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_rules() -> miette::Result<()> {
+        // every stone splits into two stones with the same engraving
+        let mut engine = StoneEngine::new(|stone: u64| Some(vec![stone, stone]));
+        assert_eq!(1 << 100, engine.count(42, 100)?);
+        assert!(engine.count(42, 128).is_err());
+        assert_eq!(HashSet::from([42]), engine.distinct_stones(&[42], 10)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_distinct_stones() -> miette::Result<()> {
+        let engine = StoneEngine::new(PuzzleRules);
+        assert_eq!(
+            HashSet::from([1, 2024, 0, 9, 2021976]),
+            engine.distinct_stones(&[0, 1, 10, 99, 999], 1)?
+        );
+        // the set of engravings stays small while the number of stones explodes
+        assert!(engine.distinct_stones(&[125, 17], 75)?.len() < 5_000);
+        Ok(())
+    }
+}
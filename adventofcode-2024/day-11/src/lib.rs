@@ -1,2 +1,45 @@
+use crate::engine::{PuzzleRules, StoneEngine};
+use miette::miette;
+use nom::character::complete;
+use nom::character::complete::space1;
+use nom::multi::separated_list1;
+use nom::IResult;
+
+pub mod engine;
 pub mod part1;
 pub mod part2;
+
+fn parse(input: &str) -> IResult<&str, Vec<u64>> {
+    separated_list1(space1, complete::u64)(input)
+}
+
+#[tracing::instrument(skip(input))]
+pub fn process_parameterized(input: &str, blinks: usize) -> miette::Result<String> {
+    let (_, stones) = parse(input).map_err(|e| miette!("parse failed {}", e))?;
+
+    let result = StoneEngine::new(PuzzleRules).count_all(&stones, blinks)?;
+
+    Ok(result.to_string())
+}
+
+/// `args` is the number of blinks
+pub fn process_with_args(input: &str, args: &str) -> miette::Result<String> {
+    let blinks = args
+        .trim()
+        .parse()
+        .map_err(|e| miette!("arg-parse failed {}", e))?;
+
+    process_parameterized(input, blinks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_process_with_args() -> miette::Result<()> {
+        assert_eq!("22", process_with_args("125 17", "6")?);
+        assert!(process_with_args("125 17", "many").is_err());
+        Ok(())
+    }
+}
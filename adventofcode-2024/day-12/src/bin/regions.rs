@@ -0,0 +1,36 @@
+use day_12::regions::Garden;
+use miette::{Context, IntoDiagnostic};
+use std::fs;
+
+/// Prints the region report and the fenced garden of `input.txt` to the terminal
+/// and optionally writes the garden as SVG.
+///
+/// `cargo run -p day-12 --bin regions -- [svg-file] [cell-size]`
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let svg_file = args.next();
+    let cell_size = match args.next() {
+        Some(cell_size) => cell_size
+            .parse()
+            .into_diagnostic()
+            .context("parse cell size")?,
+        None => 20,
+    };
+
+    let file = include_str!("../../input.txt");
+    let region_map = Garden::parse(file).regions();
+
+    print!("{}", region_map.to_ansi());
+    print!("{}", region_map.report());
+
+    if let Some(svg_file) = svg_file {
+        fs::write(&svg_file, region_map.to_svg(cell_size))
+            .into_diagnostic()
+            .context("write svg")?;
+        println!("wrote {svg_file}");
+    }
+    Ok(())
+}
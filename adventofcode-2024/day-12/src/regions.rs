@@ -0,0 +1,400 @@
+use glam::IVec2;
+
+const NEIGHBOR_DIRECTIONS: [IVec2; 4] = [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y];
+
+/// The garden as a dense grid of plants.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Garden {
+    dimensions: IVec2,
+    plants: Vec<char>,
+}
+
+impl Garden {
+    pub fn parse(input: &str) -> Garden {
+        let lines = input.lines().collect::<Vec<_>>();
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let plants = lines
+            .iter()
+            // pad ragged lines, so that every cell has a plant
+            .flat_map(|line| line.chars().chain(std::iter::repeat(' ')).take(width))
+            .collect();
+
+        Garden {
+            dimensions: IVec2::new(width as i32, lines.len() as i32),
+            plants,
+        }
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        let in_bounds =
+            pos.x >= 0 && pos.y >= 0 && pos.x < self.dimensions.x && pos.y < self.dimensions.y;
+        in_bounds.then(|| (pos.y * self.dimensions.x + pos.x) as usize)
+    }
+
+    fn position(&self, idx: usize) -> IVec2 {
+        IVec2::new(
+            idx as i32 % self.dimensions.x,
+            idx as i32 / self.dimensions.x,
+        )
+    }
+
+    pub fn plant(&self, pos: IVec2) -> Option<char> {
+        self.index(pos).map(|idx| self.plants[idx])
+    }
+
+    /// Labels every cell with its region and computes the stats of all regions in one flood fill per region.
+    pub fn regions(&self) -> RegionMap {
+        let mut labels: Vec<Option<usize>> = vec![None; self.plants.len()];
+        let mut regions = vec![];
+        let mut stack = vec![];
+
+        for start in 0..self.plants.len() {
+            if labels[start].is_some() {
+                continue;
+            }
+
+            let label = regions.len();
+            let plant = self.plants[start];
+            let mut stats = RegionStats {
+                plant,
+                area: 0,
+                perimeter: 0,
+                sides: 0,
+                bounding_box: BoundingBox {
+                    min: self.position(start),
+                    max: self.position(start),
+                },
+            };
+
+            labels[start] = Some(label);
+            stack.push(start);
+            while let Some(idx) = stack.pop() {
+                let pos = self.position(idx);
+                let is_same = |offset: IVec2| self.plant(pos + offset) == Some(plant);
+
+                stats.area += 1;
+                stats.bounding_box.min = stats.bounding_box.min.min(pos);
+                stats.bounding_box.max = stats.bounding_box.max.max(pos);
+                stats.perimeter += NEIGHBOR_DIRECTIONS
+                    .iter()
+                    .filter(|dir| !is_same(**dir))
+                    .count();
+                // a polygon has as many sides as corners
+                stats.sides += NEIGHBOR_DIRECTIONS
+                    .iter()
+                    .zip(NEIGHBOR_DIRECTIONS.iter().cycle().skip(1))
+                    .filter(|(a, b)| {
+                        let is_outer_corner = !is_same(**a) && !is_same(**b);
+                        let is_inner_corner = is_same(**a) && is_same(**b) && !is_same(**a + **b);
+                        is_outer_corner || is_inner_corner
+                    })
+                    .count();
+
+                for dir in NEIGHBOR_DIRECTIONS {
+                    if let Some(neighbor_idx) = self.index(pos + dir) {
+                        if labels[neighbor_idx].is_none() && self.plants[neighbor_idx] == plant {
+                            labels[neighbor_idx] = Some(label);
+                            stack.push(neighbor_idx);
+                        }
+                    }
+                }
+            }
+
+            regions.push(stats);
+        }
+
+        RegionMap {
+            dimensions: self.dimensions,
+            labels: labels
+                .into_iter()
+                .map(|label| label.expect("every cell has been labelled"))
+                .collect(),
+            regions,
+        }
+    }
+}
+
+/// inclusive corners of the cells a region covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BoundingBox {
+    pub min: IVec2,
+    pub max: IVec2,
+}
+
+impl BoundingBox {
+    pub fn contains(&self, pos: IVec2) -> bool {
+        pos.cmpge(self.min).all() && pos.cmple(self.max).all()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RegionStats {
+    pub plant: char,
+    pub area: usize,
+    pub perimeter: usize,
+    /// number of straight fence sections
+    pub sides: usize,
+    pub bounding_box: BoundingBox,
+}
+
+impl RegionStats {
+    /// part 1
+    pub fn price(&self) -> usize {
+        self.area * self.perimeter
+    }
+
+    /// part 2
+    pub fn bulk_discount_price(&self) -> usize {
+        self.area * self.sides
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegionMap {
+    pub dimensions: IVec2,
+    /// region index for every cell of the garden, row by row
+    pub labels: Vec<usize>,
+    pub regions: Vec<RegionStats>,
+}
+
+/// A straight fence section between two grid corners - `(0, 0)` is the top left corner of the top left cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fence {
+    pub region: usize,
+    pub from: IVec2,
+    pub to: IVec2,
+    /// points away from the region
+    pub outward: IVec2,
+}
+
+impl RegionMap {
+    /// `None` outside the garden
+    pub fn label(&self, pos: IVec2) -> Option<usize> {
+        let in_bounds =
+            pos.x >= 0 && pos.y >= 0 && pos.x < self.dimensions.x && pos.y < self.dimensions.y;
+        in_bounds.then(|| self.labels[(pos.y * self.dimensions.x + pos.x) as usize])
+    }
+
+    fn has_fence(&self, pos: IVec2, outward: IVec2) -> bool {
+        self.label(pos) != self.label(pos + outward)
+    }
+
+    /// All fences as maximal straight sections - every region has exactly `sides` of them.
+    pub fn fences(&self) -> Vec<Fence> {
+        let mut fences = vec![];
+
+        for outward in NEIGHBOR_DIRECTIONS {
+            // fences run perpendicular to the outward direction
+            let along = outward.perp().abs();
+            for y in 0..self.dimensions.y {
+                for x in 0..self.dimensions.x {
+                    let pos = IVec2::new(x, y);
+                    let previous = pos - along;
+                    let continues_previous = self.label(previous) == self.label(pos)
+                        && self.has_fence(previous, outward);
+                    if !self.has_fence(pos, outward) || continues_previous {
+                        continue;
+                    }
+
+                    let mut end = pos;
+                    while self.label(end + along) == self.label(pos)
+                        && self.has_fence(end + along, outward)
+                    {
+                        end += along;
+                    }
+
+                    // the edge of a cell on the `outward` side, in corner coordinates
+                    let offset = outward.max(IVec2::ZERO);
+                    let from = pos + offset;
+                    let to = end + offset + along;
+                    fences.push(Fence {
+                        region: self.label(pos).expect("pos is inside the garden"),
+                        from,
+                        to,
+                        outward,
+                    });
+                }
+            }
+        }
+
+        fences
+    }
+
+    /// Number of areas of other plants that are completely fenced in by each region,
+    /// e.g. the `X` plots inside the `O` region of the second example.
+    /// Only the report needs these, so they aren't part of [RegionStats].
+    pub fn holes(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.labels.len()];
+        (0..self.regions.len())
+            .map(|label| self.count_holes(label, &mut visited))
+            .collect()
+    }
+
+    /// Flood fills everything that doesn't belong to the region inside its bounding box.
+    /// Areas that reach the edge of the bounding box aren't enclosed.
+    /// `visited` is only touched inside the bounding box and reset there afterwards.
+    fn count_holes(&self, label: usize, visited: &mut [bool]) -> usize {
+        let bounding_box = self.regions[label].bounding_box;
+        let index = |pos: IVec2| (pos.y * self.dimensions.x + pos.x) as usize;
+        let mut holes = 0;
+
+        for y in bounding_box.min.y..=bounding_box.max.y {
+            for x in bounding_box.min.x..=bounding_box.max.x {
+                let start = IVec2::new(x, y);
+                if self.labels[index(start)] == label || visited[index(start)] {
+                    continue;
+                }
+
+                let mut is_enclosed = true;
+                let mut stack = vec![start];
+                visited[index(start)] = true;
+                while let Some(pos) = stack.pop() {
+                    for dir in NEIGHBOR_DIRECTIONS {
+                        let neighbor = pos + dir;
+                        if !bounding_box.contains(neighbor) {
+                            is_enclosed = false;
+                            continue;
+                        }
+                        if self.labels[index(neighbor)] != label && !visited[index(neighbor)] {
+                            visited[index(neighbor)] = true;
+                            stack.push(neighbor);
+                        }
+                    }
+                }
+
+                if is_enclosed {
+                    holes += 1;
+                }
+            }
+        }
+
+        for y in bounding_box.min.y..=bounding_box.max.y {
+            let row =
+                index(IVec2::new(bounding_box.min.x, y))..=index(IVec2::new(bounding_box.max.x, y));
+            visited[row].fill(false);
+        }
+
+        holes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn test_regions() {
+        let input = r#"
+AAAA
+BBCD
+BBCC
+EEEC
+        "#
+        .trim();
+
+        let region_map = Garden::parse(input).regions();
+        let stats = region_map
+            .regions
+            .iter()
+            .map(|r| (r.plant, r.area, r.perimeter, r.sides))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            stats,
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4)
+            ]
+        );
+        assert_eq!(region_map.labels[..4], [0, 0, 0, 0]);
+        assert_eq!(region_map.labels[15], 2);
+    }
+
+    #[rstest]
+    #[case("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO", 4)]
+    #[case("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE", 0)]
+    // the B regions touch diagonally, but are fenced separately
+    #[case("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA", 2)]
+    // a hole can contain several regions
+    #[case("AAAA\nABCA\nAAAA", 1)]
+    fn test_holes(#[case] input: &str, #[case] expected: usize) {
+        let holes = Garden::parse(input).regions().holes();
+        assert_eq!(expected, holes[0]);
+        assert!(holes[1..].iter().all(|holes| *holes == 0));
+    }
+
+    #[rstest]
+    #[case("AAAA\nBBCD\nBBCC\nEEEC")]
+    #[case("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE")]
+    #[case("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA")]
+    #[case("AB\nBA")]
+    fn test_fences_match_sides(#[case] input: &str) {
+        let region_map = Garden::parse(input).regions();
+        let fences = region_map.fences();
+
+        for (label, region) in region_map.regions.iter().enumerate() {
+            let region_fences = fences.iter().filter(|fence| fence.region == label);
+            assert_eq!(region.sides, region_fences.clone().count(), "{region:?}");
+            assert_eq!(
+                region.perimeter as i32,
+                region_fences
+                    .map(|fence| (fence.to - fence.from).abs().element_sum())
+                    .sum::<i32>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_bounding_box() {
+        let region_map = Garden::parse("AAAA\nBBCD\nBBCC\nEEEC").regions();
+        assert_eq!(
+            region_map.regions[2].bounding_box,
+            BoundingBox {
+                min: IVec2::new(2, 1),
+                max: IVec2::new(3, 3)
+            }
+        );
+    }
+
+    #[rstest]
+    // the O regions are separate, even though they have the same plant
+    #[case("OOOOO\nOXOXO\nOOOOO\nOXOXO\nOOOOO", 5, 772, 436)]
+    #[case("EEEEE\nEXXXX\nEEEEE\nEXXXX\nEEEEE", 3, 692, 236)]
+    #[case("AAAAAA\nAAABBA\nAAABBA\nABBAAA\nABBAAA\nAAAAAA", 3, 1184, 368)]
+    // diagonal neighbors don't belong to the same region
+    #[case("AB\nBA", 4, 16, 16)]
+    fn test_prices(
+        #[case] input: &str,
+        #[case] region_count: usize,
+        #[case] price: usize,
+        #[case] bulk_discount_price: usize,
+    ) {
+        let region_map = Garden::parse(input).regions();
+
+        assert_eq!(region_count, region_map.regions.len());
+        assert_eq!(
+            price,
+            region_map
+                .regions
+                .iter()
+                .map(RegionStats::price)
+                .sum::<usize>()
+        );
+        assert_eq!(
+            bulk_discount_price,
+            region_map
+                .regions
+                .iter()
+                .map(RegionStats::bulk_discount_price)
+                .sum::<usize>()
+        );
+    }
+}
@@ -0,0 +1,215 @@
+use crate::regions::RegionMap;
+use glam::IVec2;
+use itertools::Itertools;
+use std::fmt::Write;
+
+/// A distinct colour per region - hues are spread by the golden angle, so neighbouring labels look different.
+pub fn region_color(label: usize) -> (u8, u8, u8) {
+    let hue = (label as f32 * 137.507_77) % 360.0;
+    hsl_to_rgb(hue, 0.6, 0.7)
+}
+
+fn hsl_to_rgb(hue: f32, saturation: f32, lightness: f32) -> (u8, u8, u8) {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let (r, g, b) = match hue as u32 / 60 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let m = lightness - chroma / 2.0;
+    let channel = |value: f32| ((value + m) * 255.0).round() as u8;
+    (channel(r), channel(g), channel(b))
+}
+
+impl RegionMap {
+    /// One line per region: label, plant, area, perimeter, sides, bounding box and holes.
+    pub fn report(&self) -> String {
+        let mut report =
+            "region plant  area perimeter sides  bounding box        holes\n".to_string();
+        for (label, (region, holes)) in self.regions.iter().zip(self.holes()).enumerate() {
+            let bounding_box = format!(
+                "{},{}..{},{}",
+                region.bounding_box.min.x,
+                region.bounding_box.min.y,
+                region.bounding_box.max.x,
+                region.bounding_box.max.y
+            );
+            writeln!(
+                report,
+                "{label:>6} {:>5} {:>5} {:>9} {:>5}  {bounding_box:<18} {holes:>5}",
+                region.plant, region.area, region.perimeter, region.sides
+            )
+            .expect("writing to a string can't fail");
+        }
+        report
+    }
+
+    /// Fills every region with its own colour and draws every side as a separate line,
+    /// slightly shortened so that the ends of two sides meeting at a corner stay visible.
+    pub fn to_svg(&self, cell_size: i32) -> String {
+        let size = self.dimensions * cell_size;
+        let mut svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+            size.x, size.y, size.x, size.y
+        );
+        svg.push('\n');
+
+        for y in 0..self.dimensions.y {
+            for x in 0..self.dimensions.x {
+                let label = self
+                    .label(IVec2::new(x, y))
+                    .expect("position is inside the garden");
+                let (r, g, b) = region_color(label);
+                writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{cell_size}" height="{cell_size}" fill="rgb({r},{g},{b})"/>"#,
+                    x * cell_size,
+                    y * cell_size,
+                )
+                .expect("writing to a string can't fail");
+                writeln!(
+                    svg,
+                    r#"<text x="{}" y="{}" font-size="{}" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                    x * cell_size + cell_size / 2,
+                    y * cell_size + cell_size / 2,
+                    cell_size / 2,
+                    self.regions[label].plant
+                )
+                .expect("writing to a string can't fail");
+            }
+        }
+
+        let inset = cell_size as f32 / 8.0;
+        for fence in self.fences() {
+            // move the fence into its region, so the fences of both neighbours are visible
+            let shift = fence.outward.as_vec2() * -inset / 2.0;
+            let along = (fence.to - fence.from).as_vec2().normalize() * inset;
+            let from = (fence.from * cell_size).as_vec2() + shift + along;
+            let to = (fence.to * cell_size).as_vec2() + shift - along;
+            writeln!(
+                svg,
+                r#"<line x1="{}" y1="{}" x2="{}" y2="{}" stroke="black" stroke-width="{}" stroke-linecap="round"/>"#,
+                from.x,
+                from.y,
+                to.x,
+                to.y,
+                inset / 2.0
+            )
+            .expect("writing to a string can't fail");
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// The garden with coloured regions for the terminal.
+    /// Every cell is surrounded by its edges, which show `│` and `─` where a fence is.
+    pub fn to_ansi(&self) -> String {
+        let mut ansi = String::new();
+
+        for y in 0..=self.dimensions.y * 2 {
+            for x in 0..=self.dimensions.x * 2 {
+                // cells are at odd coordinates, edges and corners in between
+                let cell = IVec2::new(x / 2, y / 2);
+                let (glyph, label) = match (x % 2, y % 2) {
+                    (1, 1) => {
+                        let label = self.label(cell).expect("position is inside the garden");
+                        (self.regions[label].plant, Some(label))
+                    }
+                    (0, 1) => self.edge(cell - IVec2::X, cell, '│'),
+                    (1, 0) => self.edge(cell - IVec2::Y, cell, '─'),
+                    _ => {
+                        // a corner touches four cells
+                        let has_fence = ![IVec2::ZERO, IVec2::NEG_X, IVec2::NEG_Y, IVec2::NEG_ONE]
+                            .iter()
+                            .map(|offset| self.label(cell + *offset))
+                            .all_equal();
+                        (if has_fence { '+' } else { ' ' }, None)
+                    }
+                };
+
+                match label {
+                    Some(label) => {
+                        let (r, g, b) = region_color(label);
+                        write!(ansi, "\x1b[30;48;2;{r};{g};{b}m{glyph}\x1b[0m")
+                    }
+                    None => write!(ansi, "{glyph}"),
+                }
+                .expect("writing to a string can't fail");
+            }
+            ansi.push('\n');
+        }
+
+        ansi
+    }
+
+    /// A fence glyph between two cells or a blank in the colour of the region that spans both.
+    fn edge(&self, a: IVec2, b: IVec2, fence: char) -> (char, Option<usize>) {
+        match (self.label(a), self.label(b)) {
+            (Some(a), Some(b)) if a == b => (' ', Some(a)),
+            _ => (fence, None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::regions::Garden;
+
+    const NESTED_E: &str = "EEEEE
+EXXXX
+EEEEE
+EXXXX
+EEEEE";
+
+    const NESTED_AB: &str = "AAAAAA
+AAABBA
+AAABBA
+ABBAAA
+ABBAAA
+AAAAAA";
+
+    #[test]
+    fn test_report() {
+        let report = Garden::parse(NESTED_AB).regions().report();
+        let expected = "region plant  area perimeter sides  bounding box        holes
+     0     A    28        40    12  0,0..5,5               2
+     1     B     4         8     4  3,1..4,2               0
+     2     B     4         8     4  1,3..2,4               0
+";
+        assert_eq!(expected, report);
+    }
+
+    #[test]
+    fn test_svg_draws_every_side() {
+        let region_map = Garden::parse(NESTED_E).regions();
+        let svg = region_map.to_svg(10);
+        let sides = region_map
+            .regions
+            .iter()
+            .map(|region| region.sides)
+            .sum::<usize>();
+        assert_eq!(sides, svg.matches("<line").count());
+        assert_eq!(25, svg.matches("<rect").count());
+    }
+
+    #[test]
+    fn test_ansi_outlines() {
+        let ansi = Garden::parse("AAB\nAAB").regions().to_ansi();
+        let plain = ansi
+            .split('\x1b')
+            .map(|part| part.split_once('m').map_or(part, |(_, text)| text))
+            .collect::<String>();
+        let expected = "+─+─+─+
+│A A│B│
++   + +
+│A A│B│
++─+─+─+
+";
+        assert_eq!(expected, plain);
+    }
+}
@@ -1,4 +1,6 @@
+use crate::solver::{solve, Overflow, Presses, TokenCosts};
 use glam::U64Vec2;
+use miette::miette;
 use nom::bytes::complete::take_till;
 use nom::character::complete;
 use nom::character::complete::line_ending;
@@ -8,30 +10,70 @@ use nom::IResult;
 
 pub mod part1;
 pub mod part2;
+pub mod solver;
 
+/// The cheapest way to win the prize of `machine` after moving the prize by `offset` on both axes.
 fn eval_machine(
-    Machine {
-        button_a,
-        button_b,
-        prize,
-    }: Machine,
-) -> Option<U64Vec2> {
-    // took the solution from HyperNeutrino on YouTube
-    let ax = button_a.x as f64;
-    let ay = button_a.y as f64;
-    let bx = button_b.x as f64;
-    let by = button_b.y as f64;
-    let px = prize.x as f64;
-    let py = prize.y as f64;
-
-    let ca = (px * by - py * bx) / (ax * by - ay * bx);
-    let cb = (px - ax * ca) / bx;
-
-    let ca_i64 = ca as u64;
-    let cb_i64 = cb as u64;
-
-    // only integer solutions are valid
-    (ca == ca_i64 as f64 && cb == cb_i64 as f64).then_some(U64Vec2::new(ca_i64, cb_i64))
+    machine: &Machine,
+    offset: u64,
+    costs: TokenCosts,
+) -> Result<Option<Presses>, Overflow> {
+    let as_array = |vec: U64Vec2, offset: u64| {
+        [
+            vec.x as i128 + offset as i128,
+            vec.y as i128 + offset as i128,
+        ]
+    };
+
+    solve(
+        as_array(machine.button_a, 0),
+        as_array(machine.button_b, 0),
+        as_array(machine.prize, offset),
+        costs,
+    )
+}
+
+/// Fewest tokens to win all winnable prizes - fails if the offset or the costs are too large to count them exactly.
+#[tracing::instrument(skip(input))]
+pub fn process_parameterized(
+    input: &str,
+    offset: u64,
+    costs: TokenCosts,
+) -> miette::Result<String> {
+    let (_, machines) = parse(input).map_err(|e| miette!("parse failed {}", e))?;
+
+    let mut result: i128 = 0;
+    for (idx, machine) in machines.iter().enumerate() {
+        let tokens = eval_machine(machine, offset, costs)
+            .and_then(|presses| presses.map_or(Ok(0), |presses| presses.tokens(costs)))
+            .map_err(|_| miette!("machine {}: token count overflows", idx + 1))?;
+        result = result
+            .checked_add(tokens)
+            .ok_or_else(|| miette!("total token count overflows"))?;
+    }
+
+    Ok(result.to_string())
+}
+
+/// `args` is the prize offset, optionally followed by the token costs of button A and B, e.g. `10000000000000 3 1`
+pub fn process_with_args(input: &str, args: &str) -> miette::Result<String> {
+    let numbers = args
+        .split_whitespace()
+        .map(|number| number.parse::<u64>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| miette!("arg-parse failed {}", e))?;
+
+    let (offset, costs) = match numbers[..] {
+        [offset] => (offset, TokenCosts::default()),
+        [offset, a, b] => (offset, TokenCosts { a, b }),
+        _ => {
+            return Err(miette!(
+                "arg-parse failed: expected 'offset' or 'offset cost-a cost-b', got '{args}'"
+            ))
+        }
+    };
+
+    process_parameterized(input, offset, costs)
 }
 
 #[derive(Debug)]
@@ -41,15 +83,6 @@ struct Machine {
     prize: U64Vec2,
 }
 
-impl Machine {
-    pub(crate) fn with_fixed_amount_added_to_price_coords(&self, fixed_amount: u64) -> Self {
-        Self {
-            prize: U64Vec2::new(self.prize.x + fixed_amount, self.prize.y + fixed_amount),
-            ..*self
-        }
-    }
-}
-
 fn u_vec2_parser(input: &str) -> IResult<&str, U64Vec2> {
     // will match
     // Button B: X+22, Y+67
@@ -83,3 +116,42 @@ fn machine_parser(input: &str) -> IResult<&str, Machine> {
 fn parse(input: &str) -> IResult<&str, Vec<Machine>> {
     separated_list1(tuple((line_ending, line_ending)), machine_parser)(input)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "Button A: X+94, Y+34
+Button B: X+22, Y+67
+Prize: X=8400, Y=5400
+
+Button A: X+26, Y+66
+Button B: X+67, Y+21
+Prize: X=12748, Y=12176";
+
+    #[test]
+    fn test_process_with_args() -> miette::Result<()> {
+        assert_eq!("280", process_with_args(EXAMPLE, "0")?);
+        // 80 * 1 + 40 * 1
+        assert_eq!("120", process_with_args(EXAMPLE, "0 1 1")?);
+        assert!(process_with_args(EXAMPLE, "0 1").is_err());
+        assert!(process_with_args(EXAMPLE, "far").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_overflow() -> miette::Result<()> {
+        let input = "Button A: X+1, Y+0\nButton B: X+0, Y+1\nPrize: X=0, Y=0";
+        let max = u64::MAX;
+        assert!(process_with_args(input, &format!("{max} {max} {max}")).is_err());
+        // u64::MAX presses of each button at 1 token fit
+        assert_eq!(
+            (2 * max as i128).to_string(),
+            process_with_args(input, &format!("{max} 1 1"))?
+        );
+        // every machine fits, the sum doesn't
+        let machines = [input; 3].join("\n\n");
+        assert!(process_with_args(&machines, &format!("{max} {} 1", 1u64 << 62)).is_err());
+        Ok(())
+    }
+}
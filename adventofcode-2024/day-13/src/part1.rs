@@ -1,16 +1,8 @@
-use miette::miette;
+use crate::solver::TokenCosts;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, machines) = crate::parse(input).map_err(|e| miette!("parse failed {}", e))?;
-
-    let result = machines
-        .into_iter()
-        .filter_map(crate::eval_machine)
-        .map(|res| res.x * 3 + res.y)
-        .sum::<u64>();
-
-    Ok(result.to_string())
+    crate::process_parameterized(input, 0, TokenCosts::default())
 }
 
 #[cfg(test)]
@@ -0,0 +1,295 @@
+/// Tokens needed for one press of each button.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenCosts {
+    pub a: u64,
+    pub b: u64,
+}
+
+impl Default for TokenCosts {
+    /// the costs from the puzzle: 3 tokens for A, 1 token for B
+    fn default() -> Self {
+        TokenCosts { a: 3, b: 1 }
+    }
+}
+
+/// An intermediate value or the token count doesn't fit into an `i128`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Presses {
+    pub a: i128,
+    pub b: i128,
+}
+
+impl Presses {
+    pub fn tokens(&self, costs: TokenCosts) -> Result<i128, Overflow> {
+        let a = self.a.checked_mul(costs.a as i128).ok_or(Overflow)?;
+        let b = self.b.checked_mul(costs.b as i128).ok_or(Overflow)?;
+        a.checked_add(b).ok_or(Overflow)
+    }
+}
+
+/// The cheapest non-negative number of presses with `a * button_a + b * button_b == prize`, computed exactly.
+///
+/// If the buttons are independent, Cramer's rule gives the only candidate.
+/// If they are collinear there can be many solutions along the line - the cheapest of them is returned.
+/// Fails instead of wrapping if an intermediate value or the token count of that solution doesn't fit into an `i128`.
+pub fn solve(
+    button_a: [i128; 2],
+    button_b: [i128; 2],
+    prize: [i128; 2],
+    costs: TokenCosts,
+) -> Result<Option<Presses>, Overflow> {
+    let cross = |u: [i128; 2], v: [i128; 2]| {
+        let lhs = u[0].checked_mul(v[1]).ok_or(Overflow)?;
+        let rhs = u[1].checked_mul(v[0]).ok_or(Overflow)?;
+        lhs.checked_sub(rhs).ok_or(Overflow)
+    };
+
+    let determinant = cross(button_a, button_b)?;
+    if determinant != 0 {
+        let a = cross(prize, button_b)?;
+        let b = cross(button_a, prize)?;
+        let is_integer = a % determinant == 0 && b % determinant == 0;
+        let presses = Presses {
+            a: a / determinant,
+            b: b / determinant,
+        };
+        if !(is_integer && presses.a >= 0 && presses.b >= 0) {
+            return Ok(None);
+        }
+        presses.tokens(costs)?;
+        return Ok(Some(presses));
+    }
+
+    // buttons (and prize) all on one line through the origin
+    if cross(button_a, prize)? != 0 || cross(button_b, prize)? != 0 {
+        return Ok(None);
+    }
+    if button_a == [0, 0] && button_b == [0, 0] {
+        return Ok((prize == [0, 0]).then_some(Presses { a: 0, b: 0 }));
+    }
+    // any axis on which the buttons move works, the other one follows from collinearity
+    let axis = if button_a[0] != 0 || button_b[0] != 0 {
+        0
+    } else {
+        1
+    };
+    solve_on_line(button_a[axis], button_b[axis], prize[axis], costs)
+}
+
+/// Cheapest non-negative `a * u + b * v == w`.
+fn solve_on_line(
+    u: i128,
+    v: i128,
+    w: i128,
+    costs: TokenCosts,
+) -> Result<Option<Presses>, Overflow> {
+    let presses = match (u, v) {
+        (0, 0) => return Ok((w == 0).then_some(Presses { a: 0, b: 0 })),
+        // pressing a button that doesn't move the claw never helps
+        (0, v) => (w % v == 0 && w / v >= 0).then_some(Presses { a: 0, b: w / v }),
+        (u, 0) => (w % u == 0 && w / u >= 0).then_some(Presses { a: w / u, b: 0 }),
+        _ => return solve_on_line_with_both(u, v, w, costs),
+    };
+    if let Some(presses) = presses {
+        presses.tokens(costs)?;
+    }
+    Ok(presses)
+}
+
+/// [solve_on_line] for two buttons that both move the claw.
+fn solve_on_line_with_both(
+    u: i128,
+    v: i128,
+    w: i128,
+    costs: TokenCosts,
+) -> Result<Option<Presses>, Overflow> {
+    let (gcd, x, y) = extended_gcd(u, v);
+    if w % gcd != 0 {
+        return Ok(None);
+    }
+    // all solutions: a = a0 + k * step_a, b = b0 - k * step_b
+    let a0 = x.checked_mul(w / gcd).ok_or(Overflow)?;
+    let b0 = y.checked_mul(w / gcd).ok_or(Overflow)?;
+    let (step_a, step_b) = (v / gcd, u / gcd);
+
+    // the range of k for which both a and b are non-negative
+    let mut k_min = None;
+    let mut k_max = None;
+    let mut restrict = |start: i128, step: i128| -> Result<(), Overflow> {
+        // start + k * step >= 0
+        let neg_start = start.checked_neg().ok_or(Overflow)?;
+        if step > 0 {
+            let bound = div_ceil(neg_start, step);
+            k_min = Some(k_min.map_or(bound, |k_min: i128| k_min.max(bound)));
+        } else {
+            let bound = div_floor(neg_start, step);
+            k_max = Some(k_max.map_or(bound, |k_max: i128| k_max.min(bound)));
+        }
+        Ok(())
+    };
+    restrict(a0, step_a)?;
+    restrict(b0, -step_b)?;
+
+    let presses_at = |k: i128| -> Result<Presses, Overflow> {
+        let a = k
+            .checked_mul(step_a)
+            .and_then(|step| a0.checked_add(step))
+            .ok_or(Overflow)?;
+        let b = k
+            .checked_mul(step_b)
+            .and_then(|step| b0.checked_sub(step))
+            .ok_or(Overflow)?;
+        Ok(Presses { a, b })
+    };
+    if let (Some(k_min), Some(k_max)) = (k_min, k_max) {
+        if k_min > k_max {
+            return Ok(None);
+        }
+    }
+    // the cost is linear in k, so the cheapest solution is at one end of the range.
+    // If the range is open on one side, both presses grow in that direction, so the closed end is the cheapest.
+    // An end whose token count overflows is more expensive than one that fits.
+    let ends = [k_min, k_max]
+        .into_iter()
+        .flatten()
+        .map(presses_at)
+        .collect::<Result<Vec<_>, _>>()?;
+    if ends.is_empty() {
+        return Ok(None);
+    }
+    ends.into_iter()
+        .filter_map(|presses| Some((presses.tokens(costs).ok()?, presses)))
+        .min_by_key(|(tokens, _)| *tokens)
+        .map(|(_, presses)| Some(presses))
+        .ok_or(Overflow)
+}
+
+/// `(gcd, x, y)` with `u * x + v * y == gcd` and `gcd > 0`
+fn extended_gcd(u: i128, v: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (u, v);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_x, x) = (x, old_x - quotient * x);
+        (old_y, y) = (y, old_y - quotient * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+fn div_floor(numerator: i128, denominator: i128) -> i128 {
+    let quotient = numerator / denominator;
+    if numerator % denominator != 0 && (numerator < 0) != (denominator < 0) {
+        quotient - 1
+    } else {
+        quotient
+    }
+}
+
+fn div_ceil(numerator: i128, denominator: i128) -> i128 {
+    -div_floor(-numerator, denominator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case([94, 34], [22, 67], [8400, 5400], Some(Presses { a: 80, b: 40 }))]
+    #[case([26, 66], [67, 21], [12748, 12176], None)]
+    #[case([26, 66], [67, 21], [10000000012748, 10000000012176], Some(Presses { a: 118679050709, b: 103199174542 }))]
+    // collinear: B is cheaper per distance
+    #[case([2, 2], [1, 1], [10, 10], Some(Presses { a: 0, b: 10 }))]
+    // collinear: A is cheaper per distance
+    #[case([5, 5], [1, 1], [10, 10], Some(Presses { a: 2, b: 0 }))]
+    #[case([4, 4], [6, 6], [14, 14], Some(Presses { a: 2, b: 1 }))]
+    #[case([4, 4], [6, 6], [15, 15], None)]
+    #[case([2, 2], [3, 3], [4, 5], None)]
+    #[case([0, 0], [0, 5], [0, 15], Some(Presses { a: 0, b: 3 }))]
+    #[case([0, 0], [0, 0], [0, 0], Some(Presses { a: 0, b: 0 }))]
+    #[case([0, 0], [0, 0], [1, 0], None)]
+    fn test_solve(
+        #[case] button_a: [i128; 2],
+        #[case] button_b: [i128; 2],
+        #[case] prize: [i128; 2],
+        #[case] expected: Option<Presses>,
+    ) {
+        assert_eq!(
+            Ok(expected),
+            solve(button_a, button_b, prize, TokenCosts::default())
+        );
+    }
+
+    #[rstest]
+    // the determinant doesn't fit
+    #[case([i128::MAX, 0], [0, 2], [0, 0], TokenCosts::default())]
+    // the only solution presses A u64::MAX times at u64::MAX tokens each
+    #[case([1, 0], [0, 1], [u64::MAX as i128, 0], TokenCosts { a: u64::MAX, b: 1 })]
+    // collinear, both ends of the line cost too much
+    #[case([1, 1], [2, 2], [2 * u64::MAX as i128, 2 * u64::MAX as i128], TokenCosts { a: u64::MAX, b: u64::MAX })]
+    fn test_solve_overflow(
+        #[case] button_a: [i128; 2],
+        #[case] button_b: [i128; 2],
+        #[case] prize: [i128; 2],
+        #[case] costs: TokenCosts,
+    ) {
+        assert_eq!(Err(Overflow), solve(button_a, button_b, prize, costs));
+    }
+
+    #[test]
+    fn test_collinear_matches_brute_force() {
+        for costs in [TokenCosts { a: 3, b: 1 }, TokenCosts { a: 1, b: 5 }] {
+            for (u, v) in [(4, 6), (3, 5), (7, 2), (5, 5), (9, 6)] {
+                for w in 0..80 {
+                    let brute_force = (0..=w / u)
+                        .flat_map(|a| (0..=w / v).map(move |b| Presses { a, b }))
+                        .filter(|presses| presses.a * u + presses.b * v == w)
+                        .map(|presses| presses.tokens(costs).unwrap())
+                        .min();
+                    let solved = solve([u, 2 * u], [v, 2 * v], [w, 2 * w], costs).unwrap();
+                    assert_eq!(
+                        brute_force,
+                        solved.map(|presses| presses.tokens(costs).unwrap()),
+                        "{u} {v} {w}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_collinear_overflowing_end() {
+        // pressing only A overflows, one A and the rest with B still fits
+        let u64_max = u64::MAX as i128;
+        assert_eq!(
+            Ok(Some(Presses {
+                a: 1,
+                b: u64_max / 2
+            })),
+            solve(
+                [1, 1],
+                [2, 2],
+                [u64_max, u64_max],
+                TokenCosts { a: u64::MAX, b: 1 }
+            )
+        );
+    }
+
+    #[test]
+    fn test_negative_directions() {
+        // B moves backwards, the cheapest solution presses as little as possible
+        assert_eq!(
+            Ok(Some(Presses { a: 4, b: 1 })),
+            solve([3, 0], [-2, 0], [10, 0], TokenCosts::default())
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use day_14::simulator::{Heuristic, Swarm};
+use glam::IVec2;
+use miette::{miette, Context, IntoDiagnostic};
+use std::fs;
+use std::path::PathBuf;
+
+/// Searches the frame of `input.txt` that looks most like a picture and writes it as
+/// `frame-NNNNN.pbm` and `frame-NNNNN.png`.
+///
+/// `cargo run -p day-14 --bin frames -- [unique|variance|component|entropy] [output-dir] [scale]`
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let heuristic = match args.next().as_deref() {
+        Some("unique") => Heuristic::Unique,
+        Some("variance") | None => Heuristic::Variance,
+        Some("component") => Heuristic::LargestComponent,
+        Some("entropy") => Heuristic::Entropy,
+        Some(other) => return Err(miette!("unknown heuristic '{other}'")),
+    };
+    let output_dir = PathBuf::from(args.next().unwrap_or_else(|| "frames".to_string()));
+    let scale = match args.next() {
+        Some(scale) => scale.parse().into_diagnostic().context("parse scale")?,
+        None => 4,
+    };
+
+    let file = include_str!("../../input.txt");
+    let swarm = Swarm::parse(file, IVec2::new(101, 103))?;
+    let second = swarm
+        .find_pattern(heuristic)
+        .ok_or_else(|| miette!("no frame found with {heuristic:?}"))?;
+    let frame = swarm.frame(second);
+
+    fs::create_dir_all(&output_dir)
+        .into_diagnostic()
+        .context("create output directory")?;
+    fs::write(
+        output_dir.join(format!("frame-{second:05}.pbm")),
+        frame.to_pbm(),
+    )
+    .into_diagnostic()?;
+    fs::write(
+        output_dir.join(format!("frame-{second:05}.png")),
+        frame.to_png(scale),
+    )
+    .into_diagnostic()?;
+
+    println!("{}", frame.render());
+    println!("second {second}, written to {}", output_dir.display());
+    Ok(())
+}
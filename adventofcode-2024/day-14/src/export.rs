@@ -0,0 +1,133 @@
+use crate::simulator::Frame;
+
+impl Frame {
+    /// Plain PBM (`P1`): robots are black pixels.
+    pub fn to_pbm(&self) -> String {
+        let mut pbm = format!("P1\n{} {}\n", self.dimensions.x, self.dimensions.y);
+        for row in self.robots_per_tile.chunks(self.dimensions.x as usize) {
+            let pixels = row
+                .iter()
+                .map(|count| if *count > 0 { "1" } else { "0" })
+                .collect::<Vec<_>>();
+            pbm.push_str(&pixels.join(" "));
+            pbm.push('\n');
+        }
+        pbm
+    }
+
+    /// Greyscale PNG with every tile scaled to `scale` x `scale` pixels - robots are black.
+    /// The image data is stored uncompressed, which keeps the encoder dependency free.
+    pub fn to_png(&self, scale: u32) -> Vec<u8> {
+        let width = self.dimensions.x as u32 * scale;
+        let height = self.dimensions.y as u32 * scale;
+
+        let mut raw = Vec::with_capacity(((width + 1) * height) as usize);
+        for row in self.robots_per_tile.chunks(self.dimensions.x as usize) {
+            let mut line = vec![0u8]; // filter type: none
+            for count in row {
+                let pixel = if *count > 0 { 0 } else { 255 };
+                line.extend(std::iter::repeat_n(pixel, scale as usize));
+            }
+            for _ in 0..scale {
+                raw.extend_from_slice(&line);
+            }
+        }
+
+        let mut header = vec![];
+        header.extend(width.to_be_bytes());
+        header.extend(height.to_be_bytes());
+        // bit depth 8, greyscale, deflate, adaptive filtering, no interlace
+        header.extend([8, 0, 0, 0, 0]);
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &zlib_stored(&raw));
+        write_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+}
+
+fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend((data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend(kind);
+    png.extend(data);
+    let crc = crc32(&png[start..]);
+    png.extend(crc.to_be_bytes());
+}
+
+/// zlib stream made of uncompressed deflate blocks
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let is_final = blocks.peek().is_none();
+        zlib.push(is_final as u8);
+        let length = block.len() as u16;
+        zlib.extend(length.to_le_bytes());
+        zlib.extend((!length).to_le_bytes());
+        zlib.extend(block);
+    }
+    zlib.extend(adler32(data).to_be_bytes());
+    zlib
+}
+
+fn crc32(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xedb8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use glam::IVec2;
+
+    fn frame() -> Frame {
+        Frame {
+            second: 0,
+            dimensions: IVec2::new(3, 2),
+            robots_per_tile: vec![1, 0, 2, 0, 0, 1],
+        }
+    }
+
+    #[test]
+    fn test_pbm() {
+        assert_eq!("P1\n3 2\n1 0 1\n0 0 1\n", frame().to_pbm());
+    }
+
+    #[test]
+    fn test_checksums() {
+        // reference values for "123456789"
+        assert_eq!(0xcbf4_3926, crc32(b"123456789"));
+        assert_eq!(0x091e_01de, adler32(b"123456789"));
+    }
+
+    #[test]
+    fn test_png_structure() {
+        let png = frame().to_png(2);
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert_eq!(&png[12..16], b"IHDR");
+        // 6 x 4 pixels
+        assert_eq!(&png[16..24], &[0, 0, 0, 6, 0, 0, 0, 4]);
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+    }
+}
@@ -1,2 +1,52 @@
+use glam::IVec2;
+use nom::bytes::complete::tag;
+use nom::character::complete;
+use nom::character::complete::{line_ending, space1};
+use nom::multi::separated_list1;
+use nom::sequence::{preceded, separated_pair};
+use nom::IResult;
+use nom::Parser;
+use std::fmt::{Display, Formatter};
+
+pub mod export;
 pub mod part1;
 pub mod part2;
+pub mod quadrant;
+pub mod simulator;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Robot {
+    pub position: IVec2,
+    pub velocity: IVec2,
+}
+
+/// The puzzle format, e.g. `p=0,4 v=3,-3`
+impl Display for Robot {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "p={},{} v={},{}",
+            self.position.x, self.position.y, self.velocity.x, self.velocity.y
+        )
+    }
+}
+
+fn parse_i_vec2(input: &str) -> IResult<&str, IVec2> {
+    let (remaining, (x, y)) = separated_pair(complete::i32, tag(","), complete::i32)(input)?;
+
+    Ok((remaining, IVec2::new(x, y)))
+}
+
+fn parse_robots(input: &str) -> IResult<&str, Vec<Robot>> {
+    let (rest, robots) = separated_list1(
+        line_ending,
+        separated_pair(
+            preceded(tag("p="), parse_i_vec2),
+            space1,
+            preceded(tag("v="), parse_i_vec2),
+        )
+        .map(|(position, velocity)| Robot { position, velocity }),
+    )(input)?;
+
+    Ok((rest, robots))
+}
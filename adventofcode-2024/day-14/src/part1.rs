@@ -1,120 +1,44 @@
-use crate::part1::Quadrant::{BottomLeft, BottomRight, TopLeft, TopRight};
+use crate::simulator::Swarm;
 use glam::IVec2;
-use itertools::Itertools;
 use miette::miette;
 use nom::bytes::complete::tag;
 use nom::character::complete;
-use nom::character::complete::{line_ending, space1};
-use nom::multi::separated_list1;
-use nom::sequence::{preceded, separated_pair};
+use nom::character::complete::space1;
+use nom::combinator::opt;
+use nom::sequence::{preceded, separated_pair, tuple};
 use nom::IResult;
-use nom::Parser;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
-    process_parameterized(input, IVec2::new(101, 103))
+    process_parameterized(input, IVec2::new(101, 103), 100)
 }
 
-fn parse_args(args: &str) -> IResult<&str, IVec2> {
-    let (remaining, (x, y)): (&str, (i32, i32)) =
-        separated_pair(complete::i32, tag(","), complete::i32)(args)?;
+/// `width,height`, optionally followed by the number of seconds, e.g. `11,7 100`
+fn parse_args(args: &str) -> IResult<&str, (IVec2, Option<i64>)> {
+    let (remaining, ((x, y), seconds)) = tuple((
+        separated_pair(complete::i32, tag(","), complete::i32),
+        opt(preceded(space1, complete::i64)),
+    ))(args)?;
 
-    Ok((remaining, IVec2::new(x, y)))
+    Ok((remaining, (IVec2::new(x, y), seconds)))
 }
 
 pub fn process_with_args(input: &str, args: &str) -> miette::Result<String> {
-    let (_, game_field_dimensions) =
-        parse_args(args).map_err(|e| miette!("arg-parse failed {}", e))?;
+    let (_, (game_field_dimensions, seconds)) =
+        parse_args(args.trim()).map_err(|e| miette!("arg-parse failed {}", e))?;
 
-    Ok(process_parameterized(input, game_field_dimensions)?)
+    process_parameterized(input, game_field_dimensions, seconds.unwrap_or(100))
 }
 
 #[tracing::instrument]
-pub fn process_parameterized(input: &str, game_field_dimensions: IVec2) -> miette::Result<String> {
-    let width = game_field_dimensions.x;
-    let height = game_field_dimensions.y;
-
-    let median_width = width / 2;
-    let median_height = height / 2;
-
-    let (_, robots) = parse_robots(input).map_err(|e| miette!("parse failed {}", e))?;
-
-    let seconds = 100;
-
-    // dbg!(&robots);
-
-    let quadrant_counts = robots
-        .into_iter()
-        .map(|robot| {
-            let final_pos = robot.position + robot.velocity * seconds;
-            IVec2::new(
-                final_pos.x.rem_euclid(width),
-                final_pos.y.rem_euclid(height),
-            )
-        })
-        .filter_map(|pos| {
-            let not_on_median = pos.x != median_width && pos.y != median_height;
-
-            not_on_median.then_some(if pos.x > median_width {
-                // RIGHT
-                if pos.y > median_height {
-                    BottomRight
-                } else {
-                    TopRight
-                }
-            } else {
-                //LEFT
-                if pos.y > median_height {
-                    BottomLeft
-                } else {
-                    TopLeft
-                }
-            })
-        })
-        .counts();
-
-    //dbg!(&quadrant_counts);
-
-    let result = quadrant_counts
-        .values()
-        .map(|count| *count as u64)
-        .product::<u64>();
-
-    Ok(result.to_string())
-}
-
-#[derive(Debug, Eq, PartialEq, Hash)]
-enum Quadrant {
-    TopLeft,
-    TopRight,
-    BottomRight,
-    BottomLeft,
-}
-
-fn parse_i_vec2(input: &str) -> IResult<&str, IVec2> {
-    let (remaining, (x, y)) = separated_pair(complete::i32, tag(","), complete::i32)(input)?;
-
-    Ok((remaining, IVec2::new(x, y)))
-}
-
-#[derive(Debug)]
-struct Robot {
-    position: IVec2,
-    velocity: IVec2,
-}
-
-fn parse_robots(input: &str) -> IResult<&str, Vec<Robot>> {
-    let (rest, robots) = separated_list1(
-        line_ending,
-        separated_pair(
-            preceded(tag("p="), parse_i_vec2),
-            space1,
-            preceded(tag("v="), parse_i_vec2),
-        )
-        .map(|(position, velocity)| Robot { position, velocity }),
-    )(input)?;
-
-    Ok((rest, robots))
+pub fn process_parameterized(
+    input: &str,
+    game_field_dimensions: IVec2,
+    seconds: i64,
+) -> miette::Result<String> {
+    let swarm = Swarm::parse(input, game_field_dimensions)?;
+
+    Ok(swarm.quadrant_counts(seconds).safety_factor().to_string())
 }
 
 #[cfg(test)]
@@ -143,8 +67,21 @@ p=9,5 v=-3,-3
         .trim();
         assert_eq!(
             "12",
-            process_parameterized(input, TEST_GAME_FIELD_DIMENSIONS)?
+            process_parameterized(input, TEST_GAME_FIELD_DIMENSIONS, 100)?
         );
         Ok(())
     }
+
+    #[test]
+    fn test_process_with_args() -> miette::Result<()> {
+        let input = "p=0,0 v=1,1\np=3,0 v=0,1\np=0,3 v=1,0\np=3,3 v=0,0";
+        // even field, every robot in its own quadrant
+        assert_eq!("1", process_with_args(input, "4,4")?);
+        assert_eq!("1", process_with_args(input, "4,4 0")?);
+        // after two seconds everyone is in the bottom right
+        assert_eq!("0", process_with_args(input, "4,4 2")?);
+        assert!(process_with_args(input, "4").is_err());
+        assert!(process_with_args(input, "0,4").is_err());
+        Ok(())
+    }
 }
@@ -1,13 +1,6 @@
+use crate::simulator::{Heuristic, Swarm};
 use glam::IVec2;
-use itertools::Itertools;
 use miette::miette;
-use nom::bytes::complete::tag;
-use nom::character::complete;
-use nom::character::complete::{line_ending, space1};
-use nom::multi::separated_list1;
-use nom::sequence::{preceded, separated_pair};
-use nom::IResult;
-use nom::Parser;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
@@ -19,92 +12,60 @@ pub fn process_with_game_field_dimensions(
     input: &str,
     game_field_dimensions: IVec2,
 ) -> miette::Result<String> {
-    let width = game_field_dimensions.x;
-    let height = game_field_dimensions.y;
+    let swarm = Swarm::parse(input, game_field_dimensions)?;
 
-    let (_, mut robots) = parse_robots(input).map_err(|e| miette!("parse failed {}", e))?;
+    if swarm.robots().is_empty() {
+        return Err(miette!("no robots"));
+    }
 
-    let mut seconds = 0;
-
-    //dbg!(&robots);
-
-    let result = loop {
-        seconds += 1;
-        robots
-            .iter_mut()
-            .for_each(|r| r.position = (r.position + r.velocity).rem_euclid(game_field_dimensions));
-        if robots.iter().map(|r| r.position).all_unique() {
-            break seconds;
-        }
-    };
-
-    //write_to_file(&robots, game_field_dimensions);
+    // the per-axis variance only needs width + height frames, and doesn't rely on robots never overlapping.
+    // If width and height share a factor, the best seconds of both axes can contradict each other -
+    // then every frame of the period has to be scored.
+    let result = swarm
+        .find_pattern(Heuristic::Variance)
+        .or_else(|| swarm.find_pattern(Heuristic::Entropy))
+        .ok_or_else(|| miette!("no picture found"))?;
 
     Ok(result.to_string())
 }
 
-fn write_to_file(robots: &[Robot], game_field_dimensions: IVec2) {
-    use std::fs::File;
-    use std::io::Write;
-    let robots_txt = debug_robots(robots, game_field_dimensions);
-
-    let mut buffer = File::create("robots.txt").expect("unable to create file robots.txt");
-
-    buffer
-        .write_all(robots_txt.as_bytes())
-        .expect("unable to write string to file");
-}
-
-fn debug_robots(robots: &[Robot], game_field_dimensions: IVec2) -> String {
-    let position_counts = robots
-        .iter()
-        .map(|Robot { position, .. }| position)
-        .counts();
-
-    (0..game_field_dimensions.y)
-        .map(|y| {
-            (0..game_field_dimensions.x)
-                .map(|x| {
-                    position_counts
-                        .get(&IVec2::new(x, y))
-                        .map(|num| num.to_string())
-                        .unwrap_or(" ".to_string())
-                })
-                .join("")
-        })
-        .join("\n")
-}
-
-#[derive(Debug, Eq, PartialEq, Hash)]
-enum Quadrant {
-    TopLeft,
-    TopRight,
-    BottomRight,
-    BottomLeft,
-}
-
-fn parse_i_vec2(input: &str) -> IResult<&str, IVec2> {
-    let (remaining, (x, y)) = separated_pair(complete::i32, tag(","), complete::i32)(input)?;
-
-    Ok((remaining, IVec2::new(x, y)))
-}
-
-#[derive(Debug)]
-struct Robot {
-    position: IVec2,
-    velocity: IVec2,
-}
-
-fn parse_robots(input: &str) -> IResult<&str, Vec<Robot>> {
-    let (rest, robots) = separated_list1(
-        line_ending,
-        separated_pair(
-            preceded(tag("p="), parse_i_vec2),
-            space1,
-            preceded(tag("v="), parse_i_vec2),
-        )
-        .map(|(position, velocity)| Robot { position, velocity }),
-    )(input)?;
-
-    Ok((rest, robots))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::simulator::fixtures::robots_with_picture_at;
+    use itertools::Itertools;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(IVec2::new(31, 29), 500)]
+    // even dimensions share a factor, the picture is still consistent on both axes
+    #[case(IVec2::new(32, 28), 100)]
+    fn test_process_with_game_field_dimensions(
+        #[case] dimensions: IVec2,
+        #[case] second: i64,
+    ) -> miette::Result<()> {
+        let input = robots_with_picture_at(dimensions, second).iter().join("\n");
+        assert_eq!(
+            second.to_string(),
+            process_with_game_field_dimensions(&input, dimensions)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_contradicting_axes() -> miette::Result<()> {
+        // x is closest together at even seconds, y at odd ones
+        let input = "p=0,3 v=0,1\np=0,3 v=-1,2\np=1,0 v=-1,-3";
+        let dimensions = IVec2::new(4, 4);
+        let swarm = Swarm::parse(input, dimensions)?;
+        assert_eq!(None, swarm.find_pattern(Heuristic::Variance));
+
+        let expected = swarm.find_pattern(Heuristic::Entropy).unwrap();
+        assert_eq!(
+            expected.to_string(),
+            process_with_game_field_dimensions(input, dimensions)?
+        );
+        assert!(process_with_game_field_dimensions(input, IVec2::ZERO).is_err());
+        Ok(())
+    }
 }
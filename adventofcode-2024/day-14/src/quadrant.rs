@@ -0,0 +1,129 @@
+use crate::simulator::Swarm;
+use glam::IVec2;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Quadrant {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl Quadrant {
+    pub const ALL: [Quadrant; 4] = [
+        Quadrant::TopLeft,
+        Quadrant::TopRight,
+        Quadrant::BottomLeft,
+        Quadrant::BottomRight,
+    ];
+
+    /// The quadrant of a tile, `None` for tiles on the middle row or column.
+    /// Fields with an even width or height have no middle line on that axis, the halves just touch.
+    pub fn of(position: IVec2, dimensions: IVec2) -> Option<Quadrant> {
+        let half = |coordinate: i32, size: i32| {
+            let is_middle = size % 2 == 1 && coordinate == size / 2;
+            (!is_middle).then_some(coordinate >= (size + 1) / 2)
+        };
+
+        match (
+            half(position.x, dimensions.x)?,
+            half(position.y, dimensions.y)?,
+        ) {
+            (false, false) => Some(Quadrant::TopLeft),
+            (true, false) => Some(Quadrant::TopRight),
+            (false, true) => Some(Quadrant::BottomLeft),
+            (true, true) => Some(Quadrant::BottomRight),
+        }
+    }
+}
+
+/// Number of robots per quadrant - robots on the middle lines aren't counted.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuadrantCounts {
+    counts: [u64; 4],
+}
+
+impl QuadrantCounts {
+    pub fn get(&self, quadrant: Quadrant) -> u64 {
+        self.counts[quadrant as usize]
+    }
+
+    /// Product of the robots in all four quadrants - an empty quadrant makes it 0.
+    pub fn safety_factor(&self) -> u64 {
+        self.counts.iter().product()
+    }
+}
+
+impl FromIterator<Quadrant> for QuadrantCounts {
+    fn from_iter<T: IntoIterator<Item = Quadrant>>(quadrants: T) -> Self {
+        let mut counts = QuadrantCounts::default();
+        for quadrant in quadrants {
+            counts.counts[quadrant as usize] += 1;
+        }
+        counts
+    }
+}
+
+impl Swarm {
+    pub fn quadrant_counts(&self, second: i64) -> QuadrantCounts {
+        self.positions_at(second)
+            .into_iter()
+            .filter_map(|position| Quadrant::of(position, self.dimensions()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(IVec2::new(4, 2), IVec2::new(11, 7), Some(Quadrant::TopLeft))]
+    #[case(IVec2::new(5, 2), IVec2::new(11, 7), None)]
+    #[case(IVec2::new(6, 3), IVec2::new(11, 7), None)]
+    #[case(IVec2::new(6, 4), IVec2::new(11, 7), Some(Quadrant::BottomRight))]
+    // even sizes have no middle line
+    #[case(IVec2::new(4, 3), IVec2::new(10, 8), Some(Quadrant::TopLeft))]
+    #[case(IVec2::new(5, 3), IVec2::new(10, 8), Some(Quadrant::TopRight))]
+    #[case(IVec2::new(4, 3), IVec2::new(10, 7), None)]
+    #[case(IVec2::new(0, 7), IVec2::new(10, 8), Some(Quadrant::BottomLeft))]
+    fn test_quadrant_of(
+        #[case] position: IVec2,
+        #[case] dimensions: IVec2,
+        #[case] expected: Option<Quadrant>,
+    ) {
+        assert_eq!(expected, Quadrant::of(position, dimensions));
+    }
+
+    #[test]
+    fn test_quadrant_counts() -> miette::Result<()> {
+        let input = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+        let counts = Swarm::parse(input, IVec2::new(11, 7))?.quadrant_counts(100);
+        assert_eq!(
+            [1, 3, 4, 1],
+            Quadrant::ALL.map(|quadrant| counts.get(quadrant))
+        );
+        assert_eq!(12, counts.safety_factor());
+        Ok(())
+    }
+
+    #[test]
+    fn test_empty_quadrant() {
+        let counts = [Quadrant::TopLeft, Quadrant::TopRight, Quadrant::TopRight]
+            .into_iter()
+            .collect::<QuadrantCounts>();
+        assert_eq!(0, counts.safety_factor());
+    }
+}
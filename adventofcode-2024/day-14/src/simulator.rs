@@ -0,0 +1,456 @@
+use crate::{parse_robots, Robot};
+use glam::{DVec2, IVec2};
+use miette::miette;
+
+/// The robots on a wrapping field. Every robot moves in a straight line, so the whole swarm repeats
+/// after `lcm(width, height)` seconds and any second can be computed directly.
+#[derive(Debug, Clone)]
+pub struct Swarm {
+    dimensions: IVec2,
+    robots: Vec<Robot>,
+}
+
+/// How to recognise the frame with the Christmas tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heuristic {
+    /// the first frame in which no two robots share a tile
+    Unique,
+    /// the second with the lowest variance of the x positions and the one with the lowest variance
+    /// of the y positions - both axes repeat independently, so they are combined with the chinese remainder theorem
+    Variance,
+    /// the frame with the largest group of orthogonally connected robots
+    LargestComponent,
+    /// the frame with the lowest entropy of the robot distribution over blocks of tiles
+    Entropy,
+}
+
+/// The robot positions at one second.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frame {
+    pub second: i64,
+    pub dimensions: IVec2,
+    /// number of robots per tile, row by row
+    pub robots_per_tile: Vec<u16>,
+}
+
+impl Swarm {
+    /// Fails unless the field is at least one tile wide and high.
+    pub fn new(dimensions: IVec2, robots: Vec<Robot>) -> miette::Result<Self> {
+        if dimensions.cmple(IVec2::ZERO).any() {
+            return Err(miette!(
+                "game field dimensions must be positive, got {dimensions}"
+            ));
+        }
+        Ok(Swarm { dimensions, robots })
+    }
+
+    /// Robots in the puzzle format, e.g. `p=0,4 v=3,-3`
+    pub fn parse(input: &str, dimensions: IVec2) -> miette::Result<Self> {
+        let (_, robots) = parse_robots(input.trim()).map_err(|e| miette!("parse failed {}", e))?;
+        Swarm::new(dimensions, robots)
+    }
+
+    pub fn dimensions(&self) -> IVec2 {
+        self.dimensions
+    }
+
+    pub fn robots(&self) -> &[Robot] {
+        &self.robots
+    }
+
+    /// After this many seconds every robot is back at its start.
+    pub fn period(&self) -> i64 {
+        lcm(self.dimensions.x as i64, self.dimensions.y as i64)
+    }
+
+    fn coordinate_at(position: i32, velocity: i32, size: i32, second: i64) -> i32 {
+        let size = size as i64;
+        (position as i64 + velocity as i64 * second.rem_euclid(size)).rem_euclid(size) as i32
+    }
+
+    /// Robot positions after `second` seconds - negative seconds run the simulation backwards.
+    pub fn positions_at(&self, second: i64) -> Vec<IVec2> {
+        self.robots
+            .iter()
+            .map(|robot| {
+                IVec2::new(
+                    Self::coordinate_at(
+                        robot.position.x,
+                        robot.velocity.x,
+                        self.dimensions.x,
+                        second,
+                    ),
+                    Self::coordinate_at(
+                        robot.position.y,
+                        robot.velocity.y,
+                        self.dimensions.y,
+                        second,
+                    ),
+                )
+            })
+            .collect()
+    }
+
+    pub fn frame(&self, second: i64) -> Frame {
+        let mut robots_per_tile = vec![0; (self.dimensions.x * self.dimensions.y) as usize];
+        for position in self.positions_at(second) {
+            robots_per_tile[(position.y * self.dimensions.x + position.x) as usize] += 1;
+        }
+        Frame {
+            second,
+            dimensions: self.dimensions,
+            robots_per_tile,
+        }
+    }
+
+    /// The first second in `0..period` that looks most like a picture according to `heuristic`.
+    /// `None` if there are no robots, for [Heuristic::Unique] if robots overlap in every frame,
+    /// or for [Heuristic::Variance] if the best seconds of both axes contradict each other.
+    pub fn find_pattern(&self, heuristic: Heuristic) -> Option<i64> {
+        if self.robots.is_empty() {
+            return None;
+        }
+
+        match heuristic {
+            Heuristic::Unique => (0..self.period()).find(|second| self.frame(*second).is_unique()),
+            Heuristic::Variance => {
+                let best_x = self.lowest_variance(self.dimensions.x, |robot| {
+                    (robot.position.x, robot.velocity.x)
+                });
+                let best_y = self.lowest_variance(self.dimensions.y, |robot| {
+                    (robot.position.y, robot.velocity.y)
+                });
+                chinese_remainder(
+                    (best_x, self.dimensions.x as i64),
+                    (best_y, self.dimensions.y as i64),
+                )
+            }
+            Heuristic::LargestComponent => (0..self.period())
+                .max_by_key(|second| (self.frame(*second).largest_component(), -second)),
+            Heuristic::Entropy => (0..self.period())
+                .map(|second| (second, self.frame(second).entropy()))
+                .min_by(|(_, a), (_, b)| a.total_cmp(b))
+                .map(|(second, _)| second),
+        }
+    }
+
+    /// The first second in `0..size` with the lowest variance on one axis.
+    fn lowest_variance(&self, size: i32, axis: impl Fn(&Robot) -> (i32, i32)) -> i64 {
+        (0..size as i64)
+            .map(|second| {
+                let coordinates = self
+                    .robots
+                    .iter()
+                    .map(|robot| {
+                        let (position, velocity) = axis(robot);
+                        Self::coordinate_at(position, velocity, size, second) as f64
+                    })
+                    .collect::<Vec<_>>();
+                (second, variance(&coordinates))
+            })
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(second, _)| second)
+            .expect("the field is at least one tile wide")
+    }
+}
+
+impl Frame {
+    fn positions(&self) -> impl Iterator<Item = (IVec2, u16)> + '_ {
+        self.robots_per_tile
+            .iter()
+            .enumerate()
+            .filter(|(_, count)| **count > 0)
+            .map(|(idx, count)| {
+                (
+                    IVec2::new(
+                        idx as i32 % self.dimensions.x,
+                        idx as i32 / self.dimensions.x,
+                    ),
+                    *count,
+                )
+            })
+    }
+
+    /// no two robots on the same tile
+    pub fn is_unique(&self) -> bool {
+        self.robots_per_tile.iter().all(|count| *count <= 1)
+    }
+
+    /// Variance of the x and y coordinates of all robots.
+    pub fn variance(&self) -> DVec2 {
+        let (xs, ys): (Vec<f64>, Vec<f64>) = self
+            .positions()
+            .flat_map(|(position, count)| {
+                std::iter::repeat_n((position.x as f64, position.y as f64), count as usize)
+            })
+            .unzip();
+        DVec2::new(variance(&xs), variance(&ys))
+    }
+
+    /// Number of robots in the largest group of orthogonally connected occupied tiles.
+    pub fn largest_component(&self) -> usize {
+        let mut visited = vec![false; self.robots_per_tile.len()];
+        let mut largest = 0;
+        let index = |pos: IVec2| (pos.y * self.dimensions.x + pos.x) as usize;
+
+        for (start, _) in self.positions() {
+            if visited[index(start)] {
+                continue;
+            }
+            visited[index(start)] = true;
+            let mut size = 0;
+            let mut stack = vec![start];
+            while let Some(pos) = stack.pop() {
+                size += self.robots_per_tile[index(pos)] as usize;
+                for dir in [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y] {
+                    let neighbor = pos + dir;
+                    let in_bounds =
+                        neighbor.cmpge(IVec2::ZERO).all() && neighbor.cmplt(self.dimensions).all();
+                    if in_bounds
+                        && self.robots_per_tile[index(neighbor)] > 0
+                        && !visited[index(neighbor)]
+                    {
+                        visited[index(neighbor)] = true;
+                        stack.push(neighbor);
+                    }
+                }
+            }
+            largest = largest.max(size);
+        }
+
+        largest
+    }
+
+    /// Shannon entropy (in bits) of the distribution of robots over 4x4 blocks of tiles.
+    /// Robots that gather in a few blocks - like a picture - lower it, robots scattered all over the field raise it.
+    pub fn entropy(&self) -> f64 {
+        const BLOCK: i32 = 4;
+        let blocks_per_row = (self.dimensions.x + BLOCK - 1) / BLOCK;
+        let blocks_per_column = (self.dimensions.y + BLOCK - 1) / BLOCK;
+        let mut blocks = vec![0usize; (blocks_per_row * blocks_per_column) as usize];
+        for (position, count) in self.positions() {
+            let block = position / BLOCK;
+            blocks[(block.y * blocks_per_row + block.x) as usize] += count as usize;
+        }
+        histogram_entropy(&blocks)
+    }
+
+    /// `#` for tiles with robots, `.` for empty ones.
+    pub fn render(&self) -> String {
+        self.robots_per_tile
+            .chunks(self.dimensions.x as usize)
+            .map(|row| {
+                row.iter()
+                    .map(|count| if *count > 0 { '#' } else { '.' })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+fn variance(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+    values
+        .iter()
+        .map(|value| (value - mean).powi(2))
+        .sum::<f64>()
+        / values.len() as f64
+}
+
+fn histogram_entropy(histogram: &[usize]) -> f64 {
+    let total = histogram.iter().sum::<usize>() as f64;
+    histogram
+        .iter()
+        .filter(|count| **count > 0)
+        .map(|count| {
+            let probability = *count as f64 / total;
+            -probability * probability.log2()
+        })
+        .sum()
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a.abs()
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+fn lcm(a: i64, b: i64) -> i64 {
+    a / gcd(a, b) * b
+}
+
+/// `(gcd, x, y)` with `a * x + b * y == gcd`
+fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (gcd, x, y) = extended_gcd(b, a % b);
+        (gcd, y, x - (a / b) * y)
+    }
+}
+
+/// The smallest `t >= 0` with `t ≡ a (mod m)` and `t ≡ b (mod n)` - the moduli don't have to be coprime.
+fn chinese_remainder((a, m): (i64, i64), (b, n): (i64, i64)) -> Option<i64> {
+    let (gcd, x, _) = extended_gcd(m, n);
+    if (b - a) % gcd != 0 {
+        return None;
+    }
+    let lcm = m / gcd * n;
+    // t = a + m * k with m * k ≡ b - a (mod n)
+    let k = ((b - a) / gcd * x).rem_euclid(n / gcd);
+    Some((a + m * k).rem_euclid(lcm))
+}
+
+/// Swarms for tests that need more than the puzzle example.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use crate::Robot;
+    use glam::IVec2;
+
+    /// A block of robots that only assembles at `second`, everyone else moving randomly-ish.
+    pub(crate) fn robots_with_picture_at(dimensions: IVec2, second: i64) -> Vec<Robot> {
+        let mut robots = vec![];
+        for idx in 0..120 {
+            // where the robot is during the picture
+            let target = if idx < 64 {
+                IVec2::new(10 + idx % 8, 10 + idx / 8)
+            } else {
+                IVec2::new((idx * 7) % dimensions.x, (idx * 13) % dimensions.y)
+            };
+            let velocity = IVec2::new(idx % 13 - 6, (idx * 5) % 11 - 5);
+            let velocity = if velocity.x == 0 || velocity.y == 0 {
+                velocity + IVec2::ONE
+            } else {
+                velocity
+            };
+            let position = (target - velocity * second as i32).rem_euclid(dimensions);
+            robots.push(Robot { position, velocity });
+        }
+        robots
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::fixtures::robots_with_picture_at;
+    use super::*;
+    use rstest::rstest;
+
+    const EXAMPLE: &str = "p=0,4 v=3,-3
+p=6,3 v=-1,-3
+p=10,3 v=-1,2
+p=2,0 v=2,-1
+p=0,0 v=1,3
+p=3,0 v=-2,-2
+p=7,6 v=-1,-3
+p=3,0 v=-1,-2
+p=9,3 v=2,3
+p=7,3 v=-1,2
+p=2,4 v=2,-3
+p=9,5 v=-3,-3";
+
+    fn example_swarm() -> Swarm {
+        Swarm::parse(EXAMPLE, IVec2::new(11, 7)).unwrap()
+    }
+
+    #[test]
+    fn test_jump_matches_stepping() {
+        let swarm = example_swarm();
+        let mut positions = swarm.positions_at(0);
+        for second in 1..=200 {
+            positions = positions
+                .iter()
+                .zip(swarm.robots())
+                .map(|(position, robot)| (position + robot.velocity).rem_euclid(IVec2::new(11, 7)))
+                .collect();
+            assert_eq!(positions, swarm.positions_at(second));
+        }
+        assert_eq!(
+            swarm.positions_at(3),
+            swarm.positions_at(3 + swarm.period())
+        );
+        assert_eq!(
+            swarm.positions_at(-1),
+            swarm.positions_at(swarm.period() - 1)
+        );
+        assert_eq!(
+            swarm.positions_at(1_000_000_000_007),
+            swarm.positions_at(1_000_000_000_007 % 77)
+        );
+    }
+
+    #[test]
+    fn test_frame_after_100_seconds() {
+        // 2 robots on the first tile of the top row
+        let expected = "......#..#.
+...........
+#..........
+.##........
+.....#.....
+...##......
+.#....#....";
+        assert_eq!(expected, example_swarm().frame(100).render());
+    }
+
+    #[rstest]
+    #[case((2, 3), (3, 5), Some(8))]
+    #[case((0, 101), (0, 103), Some(0))]
+    #[case((1, 4), (3, 6), Some(9))]
+    #[case((1, 4), (2, 6), None)]
+    fn test_chinese_remainder(
+        #[case] a: (i64, i64),
+        #[case] b: (i64, i64),
+        #[case] expected: Option<i64>,
+    ) {
+        assert_eq!(expected, chinese_remainder(a, b));
+    }
+
+    fn swarm_with_picture_at(second: i64) -> Swarm {
+        let dimensions = IVec2::new(31, 29);
+        Swarm::new(dimensions, robots_with_picture_at(dimensions, second)).unwrap()
+    }
+
+    #[rstest]
+    #[case(Heuristic::Variance)]
+    #[case(Heuristic::LargestComponent)]
+    #[case(Heuristic::Entropy)]
+    fn test_find_pattern(#[case] heuristic: Heuristic) {
+        let swarm = swarm_with_picture_at(500);
+        assert_eq!(Some(500), swarm.find_pattern(heuristic));
+    }
+
+    #[test]
+    fn test_frame_scores() {
+        let swarm = swarm_with_picture_at(500);
+        let picture = swarm.frame(500);
+        let noise = swarm.frame(20);
+        assert!(picture.largest_component() >= 64);
+        assert!(picture.entropy() < noise.entropy());
+        assert!(picture.variance().x < noise.variance().x);
+    }
+
+    #[test]
+    fn test_unique() {
+        let swarm = example_swarm();
+        let second = swarm
+            .find_pattern(Heuristic::Unique)
+            .expect("robots spread out at some point");
+        assert!(swarm.frame(second).is_unique());
+        assert!((0..second).all(|earlier| !swarm.frame(earlier).is_unique()));
+    }
+
+    #[rstest]
+    #[case(IVec2::ZERO)]
+    #[case(IVec2::new(0, 7))]
+    #[case(IVec2::new(11, -7))]
+    fn test_invalid_dimensions(#[case] dimensions: IVec2) {
+        assert!(Swarm::parse(EXAMPLE, dimensions).is_err());
+        assert!(Swarm::new(dimensions, vec![]).is_err());
+    }
+}
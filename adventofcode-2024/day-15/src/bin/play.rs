@@ -0,0 +1,178 @@
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{cursor, execute, queue};
+use day_15::replay::Replay;
+use day_15::warehouse::{Direction, MoveResult, Warehouse};
+use miette::{miette, Context, IntoDiagnostic};
+use std::io::{stdout, Write};
+use std::time::Duration;
+
+/// Drives the robot of `input.txt` through the warehouse in the terminal.
+///
+/// `cargo run -p day-15 --bin play -- [interactive|replay] [box-width] [delay-ms]`
+///
+/// - `interactive`: arrow keys or `wasd` move the robot, `r` resets the warehouse, `q` quits
+/// - `replay`: performs the moves of the input with `delay-ms` between them, `q` quits
+fn main() -> miette::Result<()> {
+    let mut args = std::env::args().skip(1);
+    let mode = args.next().unwrap_or_else(|| "interactive".to_string());
+    let box_width = match args.next() {
+        Some(box_width) => box_width
+            .parse()
+            .into_diagnostic()
+            .context("parse box width")?,
+        None => 2,
+    };
+    let delay = match args.next() {
+        Some(delay) => delay.parse().into_diagnostic().context("parse delay")?,
+        None => 50,
+    };
+
+    let file = include_str!("../../input.txt");
+    let (warehouse, movement_sequence) = Warehouse::parse(file, box_width)?;
+
+    let _terminal = RawTerminal::enter()?;
+    match mode.as_str() {
+        "interactive" => interactive(warehouse),
+        "replay" => replay(
+            Replay::new(warehouse, movement_sequence),
+            Duration::from_millis(delay),
+        ),
+        other => Err(miette!("unknown mode '{other}'")),
+    }
+}
+
+/// Raw mode on the alternate screen - restored when dropped, even if the game fails.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> miette::Result<Self> {
+        terminal::enable_raw_mode().into_diagnostic()?;
+        execute!(stdout(), EnterAlternateScreen, cursor::Hide).into_diagnostic()?;
+        Ok(RawTerminal)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = execute!(stdout(), cursor::Show, LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+enum Input {
+    Move(Direction),
+    Reset,
+    Quit,
+}
+
+fn read_input(key: KeyEvent) -> Option<Input> {
+    if key.kind != KeyEventKind::Press {
+        return None;
+    }
+    match key.code {
+        KeyCode::Up | KeyCode::Char('w') => Some(Input::Move(Direction::North)),
+        KeyCode::Right | KeyCode::Char('d') => Some(Input::Move(Direction::East)),
+        KeyCode::Down | KeyCode::Char('s') => Some(Input::Move(Direction::South)),
+        KeyCode::Left | KeyCode::Char('a') => Some(Input::Move(Direction::West)),
+        KeyCode::Char('r') => Some(Input::Reset),
+        KeyCode::Char('q') | KeyCode::Esc => Some(Input::Quit),
+        _ => None,
+    }
+}
+
+fn status(result: &MoveResult) -> String {
+    match result {
+        MoveResult::UnableToMove(problem) => format!("blocked: {problem:?}"),
+        MoveResult::PlayerMovedToEmptySpot(_) => "moved".to_string(),
+        MoveResult::PlayerPushedBoxes(_, boxes) => format!("pushed {} box(es)", boxes.len()),
+    }
+}
+
+fn draw(warehouse: &Warehouse, header: &str, status: &str) -> miette::Result<()> {
+    let mut out = stdout();
+    queue!(out, cursor::MoveTo(0, 0), Clear(ClearType::All)).into_diagnostic()?;
+    // raw mode doesn't translate \n into a carriage return
+    let lines = [header.to_string(), warehouse.render_map()]
+        .join("\n")
+        .replace('\n', "\r\n");
+    queue!(
+        out,
+        Print(lines),
+        Print(format!(
+            "\r\nGPS score: {}   {status}\r\n",
+            warehouse.gps_score()
+        ))
+    )
+    .into_diagnostic()?;
+    out.flush().into_diagnostic()
+}
+
+fn interactive(initial: Warehouse) -> miette::Result<()> {
+    let header = "arrow keys / wasd: move   r: reset   q: quit";
+    let mut warehouse = initial.clone();
+    let mut moves = 0;
+    draw(&warehouse, header, "")?;
+
+    loop {
+        let Event::Key(key) = event::read().into_diagnostic()? else {
+            continue;
+        };
+        let status = match read_input(key) {
+            Some(Input::Move(direction)) => {
+                moves += 1;
+                format!(
+                    "move {moves} {}: {}",
+                    direction.as_char(),
+                    status(&warehouse.move_player(direction))
+                )
+            }
+            Some(Input::Reset) => {
+                warehouse = initial.clone();
+                moves = 0;
+                "reset".to_string()
+            }
+            Some(Input::Quit) => return Ok(()),
+            None => continue,
+        };
+        draw(&warehouse, header, &status)?;
+    }
+}
+
+fn replay(replay: Replay, delay: Duration) -> miette::Result<()> {
+    let total = replay.len();
+    let header = format!("replaying {total} moves   q: quit");
+    draw(replay.warehouse(), &header, "")?;
+
+    for step in replay {
+        draw(
+            &step.warehouse,
+            &header,
+            &format!(
+                "move {}/{total} {}: {}",
+                step.step + 1,
+                step.direction.as_char(),
+                status(&step.result)
+            ),
+        )?;
+
+        // wait for the next move, but stay responsive
+        if event::poll(delay).into_diagnostic()? {
+            if let Event::Key(key) = event::read().into_diagnostic()? {
+                if let Some(Input::Quit) = read_input(key) {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    // keep the final state on screen until a key is pressed
+    loop {
+        if let Event::Key(key) = event::read().into_diagnostic()? {
+            if key.kind == KeyEventKind::Press {
+                return Ok(());
+            }
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use crate::warehouse::{Direction, MoveResult, Warehouse};
+use glam::IVec2;
+
+/// The outcome of one move of a [Replay].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayStep {
+    /// 0 for the first move
+    pub step: usize,
+    pub direction: Direction,
+    pub result: MoveResult,
+    /// `(from, to)` of the leftmost cell of every box that moved
+    pub pushed: Vec<(IVec2, IVec2)>,
+    /// the warehouse after the move
+    pub warehouse: Warehouse,
+}
+
+/// Performs the moves one at a time and yields every intermediate state.
+#[derive(Debug, Clone)]
+pub struct Replay {
+    warehouse: Warehouse,
+    movement_sequence: Vec<Direction>,
+    next_step: usize,
+}
+
+impl Replay {
+    pub fn new(warehouse: Warehouse, movement_sequence: Vec<Direction>) -> Self {
+        Replay {
+            warehouse,
+            movement_sequence,
+            next_step: 0,
+        }
+    }
+
+    /// Parses the input and replays its movement sequence.
+    pub fn parse(input: &str, box_width: i32) -> miette::Result<Self> {
+        let (warehouse, movement_sequence) = Warehouse::parse(input, box_width)?;
+        Ok(Replay::new(warehouse, movement_sequence))
+    }
+
+    /// the state after the moves that have been yielded so far
+    pub fn warehouse(&self) -> &Warehouse {
+        &self.warehouse
+    }
+
+    pub fn remaining_moves(&self) -> &[Direction] {
+        &self.movement_sequence[self.next_step..]
+    }
+}
+
+impl Iterator for Replay {
+    type Item = ReplayStep;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let direction = *self.movement_sequence.get(self.next_step)?;
+        let step = self.next_step;
+        self.next_step += 1;
+
+        let result = self.warehouse.move_player(direction);
+        let pushed = match &result {
+            MoveResult::PlayerPushedBoxes(_, boxes) => boxes
+                .iter()
+                .map(|box_idx| {
+                    let to = self.warehouse.boxes()[*box_idx];
+                    (to - direction.offset(), to)
+                })
+                .collect(),
+            _ => vec![],
+        };
+
+        Some(ReplayStep {
+            step,
+            direction,
+            result,
+            pushed,
+            warehouse: self.warehouse.clone(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.remaining_moves().len();
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Replay {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::warehouse::MoveProblem;
+
+    const SMALL_EXAMPLE_STR: &str = r#"########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv
+<v>>v<<"#;
+
+    #[test]
+    fn test_replay_small_example() -> miette::Result<()> {
+        let steps = Replay::parse(SMALL_EXAMPLE_STR, 1)?.collect::<Vec<_>>();
+        assert_eq!(15, steps.len());
+
+        assert_eq!(Direction::West, steps[0].direction);
+        assert_eq!(
+            MoveResult::UnableToMove(MoveProblem::PlayerDirectlyBlockedByWall),
+            steps[0].result
+        );
+
+        // Move >: pushes one box
+        assert_eq!(vec![(IVec2::new(3, 1), IVec2::new(4, 1))], steps[3].pushed);
+
+        // Move >: pushes two boxes
+        let expected = r#"
+########
+#...@OO#
+##..O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+        "#
+        .trim();
+        assert_eq!(expected, steps[4].warehouse.render_map());
+        assert_eq!(
+            vec![
+                (IVec2::new(4, 1), IVec2::new(5, 1)),
+                (IVec2::new(5, 1), IVec2::new(6, 1))
+            ],
+            steps[4].pushed
+        );
+
+        // Move >: the boxes are stuck at the wall
+        assert_eq!(
+            MoveResult::UnableToMove(MoveProblem::NoSpaceToPushBoxes),
+            steps[5].result
+        );
+        assert!(steps[5].pushed.is_empty());
+
+        assert_eq!(2028, steps.last().unwrap().warehouse.gps_score());
+        Ok(())
+    }
+}
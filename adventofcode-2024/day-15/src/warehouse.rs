@@ -0,0 +1,720 @@
+use glam::IVec2;
+use miette::miette;
+use nom::character::complete::{line_ending, one_of};
+use nom::multi::{many1, separated_list1};
+use nom::sequence::{separated_pair, tuple};
+use nom::IResult;
+use std::collections::VecDeque;
+use MoveResult::{PlayerMovedToEmptySpot, PlayerPushedBoxes, UnableToMove};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+
+impl Direction {
+    pub fn offset(&self) -> IVec2 {
+        match self {
+            Direction::North => IVec2::NEG_Y,
+            Direction::East => IVec2::X,
+            Direction::South => IVec2::Y,
+            Direction::West => IVec2::NEG_X,
+        }
+    }
+
+    pub fn as_char(&self) -> char {
+        match self {
+            Direction::North => '^',
+            Direction::East => '>',
+            Direction::South => 'v',
+            Direction::West => '<',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveProblem {
+    PlayerDirectlyBlockedByWall,
+    NoSpaceToPushBoxes,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MoveResult {
+    UnableToMove(MoveProblem),
+    PlayerMovedToEmptySpot(IVec2),
+    /// the new player location and the indices of all boxes that moved
+    PlayerPushedBoxes(IVec2, Vec<usize>),
+}
+
+/// The warehouse with every tile of the original map scaled to `box_width` cells in x direction.
+/// Walls fill all of their cells, a box is a single object spanning `box_width` cells and the robot stays one cell wide.
+/// Part 1 is `box_width` 1, part 2 is `box_width` 2.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Warehouse {
+    dimensions: IVec2,
+    box_width: i32,
+    walls: Vec<bool>,
+    /// leftmost cell of every box
+    boxes: Vec<IVec2>,
+    /// the box covering each cell
+    occupancy: Vec<Option<usize>>,
+    player_location: IVec2,
+}
+
+impl Warehouse {
+    /// Parses the map and the movement sequence.
+    pub fn parse(input: &str, box_width: i32) -> miette::Result<(Warehouse, Vec<Direction>)> {
+        if box_width < 1 {
+            return Err(miette!("box width must be at least 1, got {box_width}"));
+        }
+
+        let (_, (map, moves)) =
+            parse_input(input.trim()).map_err(|e| miette!("parse failed {}", e))?;
+
+        let height = map.len() as i32;
+        let width = map.iter().map(|row| row.len()).max().unwrap_or(0) as i32 * box_width;
+        let mut warehouse = Warehouse {
+            dimensions: IVec2::new(width, height),
+            box_width,
+            walls: vec![false; (width * height) as usize],
+            boxes: vec![],
+            occupancy: vec![None; (width * height) as usize],
+            player_location: IVec2::NEG_ONE,
+        };
+
+        for (y, row) in map.iter().enumerate() {
+            for (x, char) in row.iter().enumerate() {
+                let pos = IVec2::new(x as i32 * box_width, y as i32);
+                match char {
+                    '#' => {
+                        for offset in 0..box_width {
+                            let idx = warehouse.index(pos + IVec2::X * offset);
+                            warehouse.walls[idx] = true;
+                        }
+                    }
+                    'O' => {
+                        warehouse.boxes.push(pos);
+                        warehouse.place_box(warehouse.boxes.len() - 1);
+                    }
+                    '@' => warehouse.player_location = pos,
+                    _ => {}
+                }
+            }
+        }
+        if warehouse.player_location == IVec2::NEG_ONE {
+            return Err(miette!("no robot '@' on the map"));
+        }
+
+        let movement_sequence = moves
+            .iter()
+            .flatten()
+            .map(|char| match char {
+                '^' => Direction::North,
+                '>' => Direction::East,
+                'v' => Direction::South,
+                _ => Direction::West,
+            })
+            .collect();
+
+        Ok((warehouse, movement_sequence))
+    }
+
+    fn index(&self, pos: IVec2) -> usize {
+        (pos.y * self.dimensions.x + pos.x) as usize
+    }
+
+    fn in_bounds(&self, pos: IVec2) -> bool {
+        pos.cmpge(IVec2::ZERO).all() && pos.cmplt(self.dimensions).all()
+    }
+
+    fn box_cells(&self, box_idx: usize) -> impl Iterator<Item = IVec2> {
+        let start = self.boxes[box_idx];
+        (0..self.box_width).map(move |offset| start + IVec2::X * offset)
+    }
+
+    fn place_box(&mut self, box_idx: usize) {
+        for cell in self.box_cells(box_idx).collect::<Vec<_>>() {
+            let idx = self.index(cell);
+            self.occupancy[idx] = Some(box_idx);
+        }
+    }
+
+    fn remove_box(&mut self, box_idx: usize) {
+        for cell in self.box_cells(box_idx).collect::<Vec<_>>() {
+            let idx = self.index(cell);
+            self.occupancy[idx] = None;
+        }
+    }
+
+    pub fn dimensions(&self) -> IVec2 {
+        self.dimensions
+    }
+
+    pub fn box_width(&self) -> i32 {
+        self.box_width
+    }
+
+    pub fn player_location(&self) -> IVec2 {
+        self.player_location
+    }
+
+    /// leftmost cell of every box
+    pub fn boxes(&self) -> &[IVec2] {
+        &self.boxes
+    }
+
+    /// Outside the map counts as wall.
+    pub fn is_wall(&self, pos: IVec2) -> bool {
+        !self.in_bounds(pos) || self.walls[self.index(pos)]
+    }
+
+    /// The box covering `pos`, if any.
+    pub fn box_at(&self, pos: IVec2) -> Option<usize> {
+        self.in_bounds(pos)
+            .then(|| self.occupancy[self.index(pos)])
+            .flatten()
+    }
+
+    /// All boxes that have to move if something enters `start` going in `direction` - breadth first,
+    /// every box pulls in the cells in front of all of its cells.
+    /// `None` if any of them would be pushed into a wall.
+    pub fn boxes_affected_by_push(&self, start: IVec2, direction: Direction) -> Option<Vec<usize>> {
+        let offset = direction.offset();
+        let mut affected = vec![];
+        let mut seen = vec![false; self.boxes.len()];
+        let mut open_list = VecDeque::from([start]);
+
+        while let Some(pos) = open_list.pop_front() {
+            if self.is_wall(pos) {
+                return None;
+            }
+            let Some(box_idx) = self.box_at(pos) else {
+                continue;
+            };
+            if seen[box_idx] {
+                continue;
+            }
+            seen[box_idx] = true;
+            affected.push(box_idx);
+            open_list.extend(
+                self.box_cells(box_idx)
+                    .map(|cell| cell + offset)
+                    .filter(|cell| self.box_at(*cell) != Some(box_idx)),
+            );
+        }
+
+        Some(affected)
+    }
+
+    pub fn move_player(&mut self, direction: Direction) -> MoveResult {
+        let target = self.player_location + direction.offset();
+
+        if self.is_wall(target) {
+            return UnableToMove(MoveProblem::PlayerDirectlyBlockedByWall);
+        }
+        if self.box_at(target).is_none() {
+            self.player_location = target;
+            return PlayerMovedToEmptySpot(target);
+        }
+
+        let Some(affected) = self.boxes_affected_by_push(target, direction) else {
+            return UnableToMove(MoveProblem::NoSpaceToPushBoxes);
+        };
+        for box_idx in &affected {
+            self.remove_box(*box_idx);
+        }
+        for box_idx in &affected {
+            self.boxes[*box_idx] += direction.offset();
+            self.place_box(*box_idx);
+        }
+        self.player_location = target;
+        PlayerPushedBoxes(target, affected)
+    }
+
+    /// Performs all moves and returns the final player location.
+    pub fn perform_moves(&mut self, movement_sequence: &[Direction]) -> IVec2 {
+        for direction in movement_sequence {
+            self.move_player(*direction);
+        }
+        self.player_location
+    }
+
+    /// Sum of the GPS coordinates of all boxes: 100 times the distance of the box to the top edge of the map
+    /// plus its distance to the left edge - measured to the closest edge of the box.
+    pub fn gps_score(&self) -> i64 {
+        self.boxes
+            .iter()
+            .map(|pos| pos.y as i64 * 100 + pos.x as i64)
+            .sum()
+    }
+
+    /// `O` for boxes of width 1, `[]` for width 2 and `[==]` for wider ones.
+    pub fn render_map(&self) -> String {
+        let mut rows = vec![];
+        for y in 0..self.dimensions.y {
+            let mut row = String::new();
+            for x in 0..self.dimensions.x {
+                let pos = IVec2::new(x, y);
+                let char = if pos == self.player_location {
+                    '@'
+                } else if self.is_wall(pos) {
+                    '#'
+                } else if let Some(box_idx) = self.box_at(pos) {
+                    let offset = x - self.boxes[box_idx].x;
+                    match (self.box_width, offset) {
+                        (1, _) => 'O',
+                        (_, 0) => '[',
+                        (width, offset) if offset == width - 1 => ']',
+                        _ => '=',
+                    }
+                } else {
+                    '.'
+                };
+                row.push(char);
+            }
+            rows.push(row);
+        }
+        rows.join("\n")
+    }
+}
+
+/// GPS score after the robot performed all moves of the input.
+pub fn process_with_box_width(input: &str, box_width: i32) -> miette::Result<String> {
+    let (mut warehouse, movement_sequence) = Warehouse::parse(input, box_width)?;
+
+    warehouse.perform_moves(&movement_sequence);
+
+    Ok(warehouse.gps_score().to_string())
+}
+
+type MapAndMoves = (Vec<Vec<char>>, Vec<Vec<char>>);
+
+fn parse_input(input: &str) -> IResult<&str, MapAndMoves> {
+    separated_pair(
+        separated_list1(line_ending, many1(one_of("#.@O"))),
+        tuple((line_ending, line_ending)),
+        separated_list1(line_ending, many1(one_of("^>v<"))),
+    )(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const SMALL_EXAMPLE_STR: &str = r#"########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv
+<v>>v<<"#;
+
+    const LARGER_EXAMPLE_STR: &str = r#"##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^"#;
+
+    fn larger_example_wide() -> Warehouse {
+        Warehouse::parse(LARGER_EXAMPLE_STR, 2).unwrap().0
+    }
+
+    /// Adds a box without re-parsing - for scenarios the example map doesn't contain.
+    fn insert_box(warehouse: &mut Warehouse, pos: IVec2) {
+        warehouse.boxes.push(pos);
+        warehouse.place_box(warehouse.boxes.len() - 1);
+    }
+
+    #[test]
+    fn test_movement_west() {
+        let (mut warehouse, _) = Warehouse::parse(SMALL_EXAMPLE_STR, 1).unwrap();
+        let original = warehouse.clone();
+
+        assert_eq!(
+            warehouse.move_player(Direction::West),
+            UnableToMove(MoveProblem::PlayerDirectlyBlockedByWall)
+        );
+        assert_eq!(warehouse, original);
+    }
+
+    #[test]
+    fn test_movement_east_1x() {
+        let (mut warehouse, _) = Warehouse::parse(SMALL_EXAMPLE_STR, 1).unwrap();
+        let player_location = warehouse.player_location();
+
+        assert_eq!(
+            warehouse.move_player(Direction::East),
+            PlayerMovedToEmptySpot(player_location + IVec2::X)
+        );
+        assert_eq!(
+            warehouse.boxes(),
+            Warehouse::parse(SMALL_EXAMPLE_STR, 1).unwrap().0.boxes()
+        );
+    }
+
+    #[test]
+    fn test_movement_east_2x_push_box() {
+        let (mut warehouse, _) = Warehouse::parse(SMALL_EXAMPLE_STR, 1).unwrap();
+        let player_location = warehouse.player_location();
+
+        let pos_after_1st_move = player_location + IVec2::X;
+        assert_eq!(
+            warehouse.move_player(Direction::East),
+            PlayerMovedToEmptySpot(pos_after_1st_move)
+        );
+
+        /*
+        ########
+        #..O.O.#
+        ##@.O..#
+        */
+
+        //2nd move east will push one box
+        assert!(matches!(
+            warehouse.move_player(Direction::East),
+            PlayerPushedBoxes(pos, boxes) if pos == pos_after_1st_move + IVec2::X && boxes.len() == 1
+        ));
+        assert_eq!(warehouse.box_at(IVec2::new(4, 2)), None);
+        assert!(warehouse.box_at(IVec2::new(5, 2)).is_some());
+    }
+
+    #[test]
+    fn test_small_example_final_state() {
+        let (mut warehouse, movement_sequence) = Warehouse::parse(SMALL_EXAMPLE_STR, 1).unwrap();
+        warehouse.perform_moves(&movement_sequence);
+
+        let expected_render = r#"
+########
+#....OO#
+##.....#
+#.....O#
+#.#O@..#
+#...O..#
+#...O..#
+########
+        "#
+        .trim();
+        assert_eq!(expected_render, warehouse.render_map());
+        assert_eq!(2028, warehouse.gps_score());
+    }
+
+    #[test]
+    fn test_parsing_and_printing_map() {
+        let warehouse = larger_example_wide();
+
+        assert_eq!(warehouse.dimensions(), IVec2::new(20, 10));
+        let expected_render = r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]....[]..[]##
+##....[]@.....[]..##
+##[]##....[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################
+        "#
+        .trim();
+
+        assert_eq!(warehouse.player_location(), IVec2::new(8, 4));
+        assert_eq!(warehouse.render_map(), expected_render);
+    }
+
+    #[test]
+    fn test_larger_example_push_box_west() {
+        let mut warehouse = larger_example_wide();
+        warehouse.move_player(Direction::West);
+
+        let expected_render = r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]....[]..[]##
+##...[]@......[]..##
+##[]##....[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################
+        "#
+        .trim();
+        assert_eq!(warehouse.render_map(), expected_render);
+
+        let player_location = warehouse.player_location();
+        assert_eq!(player_location, IVec2::new(7, 4));
+        assert_eq!(warehouse.box_at(player_location), None);
+        assert!(warehouse.box_at(player_location + IVec2::NEG_X).is_some());
+    }
+
+    #[test]
+    fn test_larger_example_push_t_shape_up() {
+        let mut warehouse = larger_example_wide();
+
+        // we can't create this scenario from parsing the original map, so we perform the moves
+        warehouse.perform_moves(&[Direction::West, Direction::South, Direction::West]);
+
+        let expected_render_starting_pos = r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]....[]..[]##
+##...[].......[]..##
+##[]##@...[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################
+        "#
+        .trim();
+
+        assert_eq!(warehouse.render_map(), expected_render_starting_pos);
+
+        // moving north should push three boxes
+        let result = warehouse.move_player(Direction::North);
+        assert!(matches!(result, PlayerPushedBoxes(_, boxes) if boxes.len() == 3));
+
+        let expected_render_final_pos = r#"
+####################
+##....[]....[]..[]##
+##..[][]......[]..##
+##...[].....[]..[]##
+##....@.......[]..##
+##[]##....[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################
+        "#
+        .trim();
+
+        assert_eq!(warehouse.render_map(), expected_render_final_pos);
+    }
+
+    #[rstest]
+    #[case(
+        Direction::North,
+        r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]@...[]..[]##
+##....[]......[]..##
+##[]##....[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################"#
+    )]
+    #[case(
+        Direction::South,
+        r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]....[]..[]##
+##....[]......[]..##
+##[]##..@.[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################"#
+    )]
+    fn test_larger_example_move_to_empty_space(
+        #[case] direction: Direction,
+        #[case] expected_render: &str,
+    ) {
+        let mut warehouse = larger_example_wide();
+        warehouse.move_player(direction);
+        assert_eq!(warehouse.render_map(), expected_render.trim());
+    }
+
+    #[test]
+    fn test_larger_example_push_box_east() {
+        let mut warehouse = larger_example_wide();
+        warehouse.perform_moves(&[Direction::East; 6]);
+
+        let expected_render = r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]....[]..[]##
+##....[]......@[].##
+##[]##....[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################
+        "#
+        .trim();
+
+        assert_eq!(warehouse.render_map(), expected_render);
+    }
+
+    #[rstest]
+    #[case(
+        4,
+        r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]....[]..[]##
+##....[]....@[][].##
+##[]##....[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################"#
+    )]
+    // the boxes hit the wall
+    #[case(
+        7,
+        r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]....[]..[]##
+##....[].....@[][]##
+##[]##....[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################"#
+    )]
+    fn test_larger_example_push_two_boxes_east(
+        #[case] moves: usize,
+        #[case] expected_render: &str,
+    ) {
+        let mut warehouse = larger_example_wide();
+
+        // insert a 2nd box right before the first one
+        insert_box(&mut warehouse, IVec2::new(12, 4));
+
+        let expected_render_initial = r#"
+####################
+##....[]....[]..[]##
+##............[]..##
+##..[][]....[]..[]##
+##....[]@...[][]..##
+##[]##....[]......##
+##[]....[]....[]..##
+##..[][]..[]..[][]##
+##........[]......##
+####################
+        "#
+        .trim();
+        assert_eq!(warehouse.render_map(), expected_render_initial);
+
+        warehouse.perform_moves(&vec![Direction::East; moves]);
+        assert_eq!(warehouse.render_map(), expected_render.trim());
+    }
+
+    #[test]
+    fn test_wide_box_blocked_by_one_cell() {
+        let input = "#######
+#.....#
+#..O..#
+#..@..#
+#######
+
+^^";
+        let (mut warehouse, moves) = Warehouse::parse(input, 3).unwrap();
+        let expected_render = r#"
+#####################
+###...............###
+###......[=]......###
+###......@........###
+#####################
+        "#
+        .trim();
+        assert_eq!(expected_render, warehouse.render_map());
+        let results = moves
+            .iter()
+            .map(|direction| warehouse.move_player(*direction))
+            .collect::<Vec<_>>();
+        assert!(matches!(results[0], PlayerPushedBoxes(_, _)));
+        assert_eq!(results[1], UnableToMove(MoveProblem::NoSpaceToPushBoxes));
+        assert_eq!(IVec2::new(9, 1), warehouse.boxes()[0]);
+    }
+
+    #[test]
+    fn test_staggered_wide_boxes() {
+        // the robot pushes the middle of a width 3 box, which pushes two boxes above it
+        let input = "#########
+#.......#
+#.......#
+#.......#
+#...@...#
+#########
+
+^";
+        let (mut warehouse, moves) = Warehouse::parse(input, 3).unwrap();
+        warehouse.boxes.clear();
+        warehouse.occupancy.fill(None);
+        for pos in [
+            IVec2::new(11, 3),
+            IVec2::new(9, 2),
+            IVec2::new(12, 2),
+            IVec2::new(15, 1),
+        ] {
+            insert_box(&mut warehouse, pos);
+        }
+        let result = warehouse.move_player(moves[0]);
+        assert!(matches!(result, PlayerPushedBoxes(_, ref boxes) if boxes.len() == 3));
+        assert_eq!(
+            &[
+                IVec2::new(11, 2),
+                IVec2::new(9, 1),
+                IVec2::new(12, 1),
+                IVec2::new(15, 1)
+            ],
+            warehouse.boxes()
+        );
+    }
+
+    #[rstest]
+    #[case(SMALL_EXAMPLE_STR, 1, 2028)]
+    #[case(LARGER_EXAMPLE_STR, 1, 10092)]
+    #[case(LARGER_EXAMPLE_STR, 2, 9021)]
+    fn test_process_with_box_width(
+        #[case] input: &str,
+        #[case] box_width: i32,
+        #[case] expected: i64,
+    ) -> miette::Result<()> {
+        assert_eq!(
+            expected.to_string(),
+            process_with_box_width(input, box_width)?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        assert!(Warehouse::parse("###\n#.#\n###\n\n<", 1).is_err());
+        assert!(Warehouse::parse(SMALL_EXAMPLE_STR, 0).is_err());
+    }
+}
@@ -0,0 +1,310 @@
+use glam::IVec2;
+use miette::miette;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// East first - the reindeer starts facing east.
+const HEADINGS: [IVec2; 4] = [IVec2::X, IVec2::Y, IVec2::NEG_X, IVec2::NEG_Y];
+
+/// Points for moving one tile forward and for rotating 90 degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Costs {
+    pub step: u64,
+    pub turn: u64,
+}
+
+impl Default for Costs {
+    fn default() -> Self {
+        Costs {
+            step: 1,
+            turn: 1000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solution {
+    pub best_score: u64,
+    /// every tile that is part of at least one best path, row by row
+    pub best_path_tiles: Vec<IVec2>,
+    /// number of different tile sequences from start to end with the best score - saturates at `u128::MAX`
+    pub best_path_count: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Maze {
+    dimensions: IVec2,
+    walls: Vec<bool>,
+    start: IVec2,
+    end: IVec2,
+}
+
+impl Maze {
+    pub fn parse(input: &str) -> miette::Result<Maze> {
+        let lines = input.trim().lines().collect::<Vec<_>>();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let mut walls = vec![true; width * lines.len()];
+        let mut start = None;
+        let mut end = None;
+
+        for (y, row) in lines.iter().enumerate() {
+            for (x, char) in row.char_indices() {
+                let pos = IVec2::new(x as i32, y as i32);
+                match char {
+                    '#' => {}
+                    '.' | 'S' | 'E' => {
+                        walls[y * width + x] = false;
+                        let tile = match char {
+                            'S' => &mut start,
+                            'E' => &mut end,
+                            _ => continue,
+                        };
+                        if let Some(first) = tile.replace(pos) {
+                            return Err(miette!(
+                                "second '{char}' tile at {pos}, the first one is at {first}"
+                            ));
+                        }
+                    }
+                    unknown => return Err(miette!("unknown char '{unknown}' at {pos}")),
+                }
+            }
+        }
+
+        Ok(Maze {
+            dimensions: IVec2::new(width as i32, lines.len() as i32),
+            walls,
+            start: start.ok_or_else(|| miette!("no start tile 'S'"))?,
+            end: end.ok_or_else(|| miette!("no end tile 'E'"))?,
+        })
+    }
+
+    /// Everything outside the map is a wall.
+    fn is_wall(&self, pos: IVec2) -> bool {
+        let in_bounds = pos.cmpge(IVec2::ZERO).all() && pos.cmplt(self.dimensions).all();
+        !in_bounds || self.walls[(pos.y * self.dimensions.x + pos.x) as usize]
+    }
+
+    fn state(&self, pos: IVec2, heading: usize) -> usize {
+        (pos.y * self.dimensions.x + pos.x) as usize * 4 + heading
+    }
+
+    fn position(&self, state: usize) -> IVec2 {
+        let tile = (state / 4) as i32;
+        IVec2::new(tile % self.dimensions.x, tile / self.dimensions.x)
+    }
+
+    /// Every transition turns (or not) and then moves one tile, so a sequence of states is exactly a sequence of tiles.
+    fn transition_cost(costs: Costs, from_heading: usize, to_heading: usize) -> u64 {
+        let turns = match (to_heading + 4 - from_heading) % 4 {
+            0 => 0,
+            2 => 2,
+            _ => 1,
+        };
+        costs.step + turns * costs.turn
+    }
+
+    /// Dijkstra over `(position, heading)` starting at `sources`.
+    /// `forward` follows the moves of the reindeer, otherwise the moves are reversed.
+    /// Returns the distance and the number of shortest paths to every state.
+    fn dijkstra(&self, sources: &[usize], costs: Costs, forward: bool) -> (Vec<u64>, Vec<u128>) {
+        let state_count = self.walls.len() * 4;
+        let mut distances = vec![u64::MAX; state_count];
+        let mut path_counts = vec![0u128; state_count];
+        let mut queue = BinaryHeap::new();
+        for source in sources {
+            distances[*source] = 0;
+            path_counts[*source] = 1;
+            queue.push(Reverse((0, *source)));
+        }
+
+        while let Some(Reverse((distance, state))) = queue.pop() {
+            if distance > distances[state] {
+                continue;
+            }
+            let pos = self.position(state);
+            let heading = state % 4;
+            if forward && pos == self.end {
+                // paths end at the first visit of the end tile
+                continue;
+            }
+
+            for (next_heading, offset) in HEADINGS.iter().enumerate() {
+                let (next_pos, cost) = if forward {
+                    (
+                        pos + *offset,
+                        Self::transition_cost(costs, heading, next_heading),
+                    )
+                } else {
+                    // undo the step in the current heading, before that the reindeer may have faced any direction
+                    (
+                        pos - HEADINGS[heading],
+                        Self::transition_cost(costs, next_heading, heading),
+                    )
+                };
+                if self.is_wall(next_pos) {
+                    continue;
+                }
+
+                let next = self.state(next_pos, next_heading);
+                let next_distance = distance + cost;
+                if next_distance < distances[next] {
+                    distances[next] = next_distance;
+                    path_counts[next] = path_counts[state];
+                    queue.push(Reverse((next_distance, next)));
+                } else if next_distance == distances[next] {
+                    path_counts[next] = path_counts[next].saturating_add(path_counts[state]);
+                }
+            }
+        }
+
+        (distances, path_counts)
+    }
+
+    /// One search from the start and one from the end: a state is on a best path
+    /// if its distance from the start plus its distance to the end is the best score.
+    pub fn solve(&self, costs: Costs) -> miette::Result<Solution> {
+        if costs.step == 0 {
+            return Err(miette!(
+                "moving forward has to cost something, otherwise there are infinitely many best paths"
+            ));
+        }
+
+        let (from_start, path_counts) = self.dijkstra(&[self.state(self.start, 0)], costs, true);
+        let end_states = (0..4)
+            .map(|heading| self.state(self.end, heading))
+            .collect::<Vec<_>>();
+
+        let best_score = end_states
+            .iter()
+            .map(|state| from_start[*state])
+            .min()
+            .filter(|score| *score != u64::MAX)
+            .ok_or_else(|| miette!("no path from {} to {}", self.start, self.end))?;
+        let best_path_count = end_states
+            .iter()
+            .filter(|state| from_start[**state] == best_score)
+            .fold(0u128, |count, state| {
+                count.saturating_add(path_counts[*state])
+            });
+
+        // the backward search starts at the end facing every direction, but only states that reach it with the best score matter
+        let best_end_states = end_states
+            .into_iter()
+            .filter(|state| from_start[*state] == best_score)
+            .collect::<Vec<_>>();
+        let (to_end, _) = self.dijkstra(&best_end_states, costs, false);
+
+        let mut best_path_tiles = (0..from_start.len())
+            .filter(|state| {
+                from_start[*state] != u64::MAX
+                    && to_end[*state] != u64::MAX
+                    && from_start[*state] + to_end[*state] == best_score
+            })
+            .map(|state| self.position(state))
+            .collect::<Vec<_>>();
+        best_path_tiles.sort_by_key(|pos| (pos.y, pos.x));
+        best_path_tiles.dedup();
+
+        Ok(Solution {
+            best_score,
+            best_path_tiles,
+            best_path_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const FIRST_EXAMPLE: &str = "###############
+#.......#....E#
+#.#.###.#.###.#
+#.....#.#...#.#
+#.###.#####.#.#
+#.#.#.......#.#
+#.#.#####.###.#
+#...........#.#
+###.#.#####.#.#
+#...#.....#.#.#
+#.#.#.###.#.#.#
+#.....#...#.#.#
+#.###.#.#.#.#.#
+#S..#.....#...#
+###############";
+
+    const SECOND_EXAMPLE: &str = "#################
+#...#...#...#..E#
+#.#.#.#.#.#.#.#.#
+#.#.#.#...#...#.#
+#.#.#.#.###.#.#.#
+#...#.#.#.....#.#
+#.#.#.#.#.#####.#
+#.#...#.#.#.....#
+#.#.#####.#.###.#
+#.#.#.......#...#
+#.#.###.#####.###
+#.#.#...#.....#.#
+#.#.#.#####.###.#
+#.#.#.........#.#
+#.#.#.#########.#
+#S#.............#
+#################";
+
+    #[rstest]
+    #[case(FIRST_EXAMPLE, 7036, 45, 3)]
+    #[case(SECOND_EXAMPLE, 11048, 64, 2)]
+    // two symmetric ways around the block
+    #[case("#####\n#...#\n#S#E#\n#...#\n#####", 3004, 8, 2)]
+    fn test_solve(
+        #[case] input: &str,
+        #[case] best_score: u64,
+        #[case] tiles: usize,
+        #[case] paths: u128,
+    ) -> miette::Result<()> {
+        let solution = Maze::parse(input)?.solve(Costs::default())?;
+        assert_eq!(best_score, solution.best_score);
+        assert_eq!(tiles, solution.best_path_tiles.len());
+        assert_eq!(paths, solution.best_path_count);
+        Ok(())
+    }
+
+    #[test]
+    fn test_custom_costs() -> miette::Result<()> {
+        // without turn costs the shortest path wins
+        let maze = Maze::parse("#######\n#.....#\n#.###.#\n#S...E#\n#######")?;
+        let solution = maze.solve(Costs { step: 1, turn: 0 })?;
+        assert_eq!(4, solution.best_score);
+        assert_eq!(1, solution.best_path_count);
+
+        let solution = maze.solve(Costs {
+            step: 1,
+            turn: 1000,
+        })?;
+        assert_eq!(4, solution.best_score);
+
+        // the start faces east, so any path to the west starts with two turns
+        let maze = Maze::parse("#####\n#E.S#\n#####")?;
+        assert_eq!(
+            2 + 2 * 7,
+            maze.solve(Costs { step: 1, turn: 7 })?.best_score
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(Maze::parse("#S#\n#x#\n#E#").is_err());
+        assert!(Maze::parse("#S#\n#S#\n#E#").is_err());
+        assert!(Maze::parse("#S#\n#E#\n#E#").is_err());
+        assert!(Maze::parse("#S#\n###\n#E#")
+            .unwrap()
+            .solve(Costs::default())
+            .is_err());
+        assert!(Maze::parse(FIRST_EXAMPLE)
+            .unwrap()
+            .solve(Costs { step: 0, turn: 1 })
+            .is_err());
+    }
+}
@@ -0,0 +1,256 @@
+use crate::computer::{Computer, Instruction, OperandType, Registers};
+use itertools::Itertools;
+use miette::miette;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete;
+use nom::character::complete::{alpha1, alphanumeric1, one_of, space0, space1};
+use nom::combinator::{all_consuming, opt, recognize};
+use nom::multi::{many0_count, many1_count};
+use nom::sequence::{pair, preceded, terminated, tuple};
+use nom::IResult;
+use std::collections::HashMap;
+
+/// An assembled program together with the registers it starts with.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Assembly {
+    pub registers: Registers,
+    pub program: Vec<u64>,
+}
+
+impl Assembly {
+    /// The program as comma-separated numbers, e.g. `0,3,5,4,3,0`.
+    pub fn program_string(&self) -> String {
+        self.program.iter().join(",")
+    }
+
+    /// The puzzle input format read by [Computer::parse].
+    pub fn to_input(&self) -> String {
+        format!(
+            "Register A: {}\nRegister B: {}\nRegister C: {}\n\nProgram: {}",
+            self.registers.a,
+            self.registers.b,
+            self.registers.c,
+            self.program_string()
+        )
+    }
+
+    pub fn into_computer(self) -> Computer {
+        Computer::new(self.registers, self.program)
+    }
+}
+
+/// The parts of one source line, comments already removed.
+#[derive(Debug, PartialEq, Eq)]
+struct Line<'a> {
+    label: Option<&'a str>,
+    statement: Option<Statement<'a>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Statement<'a> {
+    /// `.a 729` sets the initial value of a register
+    Register(char, u64),
+    Instruction(&'a str, Option<&'a str>),
+}
+
+fn identifier(input: &str) -> IResult<&str, &str> {
+    recognize(pair(
+        alt((alpha1, tag("_"))),
+        many0_count(alt((alphanumeric1, tag("_")))),
+    ))(input)
+}
+
+fn statement(input: &str) -> IResult<&str, Statement<'_>> {
+    alt((
+        |input| {
+            let (input, (register, value)) = tuple((
+                preceded(tag("."), one_of("abc")),
+                preceded(space1, complete::u64),
+            ))(input)?;
+            Ok((input, Statement::Register(register, value)))
+        },
+        |input| {
+            let (input, (mnemonic, operand)) = pair(
+                alpha1,
+                opt(preceded(
+                    space1,
+                    recognize(many1_count(alt((alphanumeric1, tag("_"))))),
+                )),
+            )(input)?;
+            Ok((input, Statement::Instruction(mnemonic, operand)))
+        },
+    ))(input)
+}
+
+fn line(input: &str) -> IResult<&str, Line<'_>> {
+    let (input, (label, statement)) = all_consuming(tuple((
+        preceded(space0, opt(terminated(identifier, tag(":")))),
+        terminated(opt(preceded(space0, statement)), space0),
+    )))(input)?;
+    Ok((input, Line { label, statement }))
+}
+
+fn operand_value(
+    instruction: Instruction,
+    operand: Option<&str>,
+    labels: &HashMap<&str, usize>,
+) -> Result<u64, String> {
+    let value = match (instruction.operand_type(), operand) {
+        (OperandType::Ignored, None) => 0,
+        (_, None) => return Err(format!("{} needs an operand", instruction.mnemonic())),
+        (_, Some(operand)) if operand.chars().all(|c| c.is_ascii_digit()) => operand
+            .parse()
+            .map_err(|_| format!("operand {operand} is too large"))?,
+        (OperandType::Combo, Some("a")) => 4,
+        (OperandType::Combo, Some("b")) => 5,
+        (OperandType::Combo, Some("c")) => 6,
+        (OperandType::Combo, Some(operand)) => {
+            return Err(format!(
+                "{} takes a number or a register, not {operand}",
+                instruction.mnemonic()
+            ))
+        }
+        (_, Some(label)) => *labels
+            .get(label)
+            .ok_or_else(|| format!("unknown label {label}"))? as u64,
+    };
+
+    if value > 7 {
+        Err(format!(
+            "operand {} is {value}, but operands only have 3 bits",
+            operand.unwrap_or_default()
+        ))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Translates mnemonic source into a program, one instruction per line:
+///
+/// ```text
+/// ; everything after a semicolon is a comment
+/// .a 2024       ; initial value of register a, b and c default to 0
+/// loop:
+///     adv 3     ; combo operands can be a number or a, b or c
+///     out a
+///     jnz loop  ; labels resolve to the address of the next instruction
+/// ```
+///
+/// The listing of [crate::disassembler::listing] assembles back to the same program.
+pub fn assemble(source: &str) -> miette::Result<Assembly> {
+    let lines = source
+        .lines()
+        .enumerate()
+        .map(|(idx, text)| {
+            let code = text.split(';').next().unwrap_or_default();
+            line(code)
+                .map(|(_, line)| (idx + 1, line))
+                .map_err(|e| miette!("line {}: cannot parse '{}': {e}", idx + 1, text.trim()))
+        })
+        .collect::<miette::Result<Vec<_>>>()?;
+
+    let mut labels = HashMap::new();
+    let mut address = 0;
+    for (line_number, line) in &lines {
+        if let Some(label) = line.label {
+            if labels.insert(label, address).is_some() {
+                return Err(miette!("line {line_number}: label {label} defined twice"));
+            }
+        }
+        if let Some(Statement::Instruction(..)) = line.statement {
+            address += 2;
+        }
+    }
+
+    let mut assembly = Assembly::default();
+    for (line_number, line) in &lines {
+        match line.statement {
+            None => {}
+            Some(Statement::Register(register, value)) => match register {
+                'a' => assembly.registers.a = value,
+                'b' => assembly.registers.b = value,
+                _ => assembly.registers.c = value,
+            },
+            Some(Statement::Instruction(mnemonic, operand)) => {
+                let instruction = Instruction::from_mnemonic(mnemonic)
+                    .ok_or_else(|| miette!("line {line_number}: unknown instruction {mnemonic}"))?;
+                let operand = operand_value(instruction, operand, &labels)
+                    .map_err(|e| miette!("line {line_number}: {e}"))?;
+                assembly.program.extend([instruction.into(), operand]);
+            }
+        }
+    }
+
+    Ok(assembly)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disassembler::listing;
+    use rstest::rstest;
+
+    #[test]
+    fn test_assemble() -> miette::Result<()> {
+        let source = r#"
+; part 1 example
+.a 729
+
+start:
+    adv 1         ; a = a >> 1
+    out a
+    jnz start
+        "#;
+        let assembly = assemble(source)?;
+        assert_eq!("0,1,5,4,3,0", assembly.program_string());
+        assert_eq!(729, assembly.registers.a);
+
+        let mut computer = Computer::parse(&assembly.to_input())?;
+        computer.run()?;
+        assert_eq!("4,6,3,5,6,3,5,2,1,0", computer.output_string());
+        assert_eq!(computer.program, assembly.into_computer().program);
+        Ok(())
+    }
+
+    #[test]
+    fn test_labels() -> miette::Result<()> {
+        let source = r#"
+    jnz skip
+    out 1
+skip: out 2 ; a label can share its line
+end:
+        "#;
+        // a = 0 never jumps
+        assert_eq!(vec![3, 4, 5, 1, 5, 2], assemble(source)?.program);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(&[0, 3, 5, 4, 3, 0])]
+    #[case(&[2, 4, 1, 5, 7, 5, 1, 6, 0, 3, 4, 6, 5, 5, 3, 0])]
+    #[case(&[4, 0, 5, 7, 6, 6, 7, 5, 2, 3])]
+    fn test_round_trip(#[case] program: &[u64]) -> miette::Result<()> {
+        let source = listing(program)?;
+        let assembly = assemble(&source)?;
+        assert_eq!(program, assembly.program);
+        assert_eq!(source, listing(&assembly.program)?);
+        Ok(())
+    }
+
+    #[rstest]
+    #[case("foo 1")]
+    #[case("adv")]
+    #[case("adv 8")]
+    #[case("bxl a")]
+    #[case("out d")]
+    #[case("jnz nowhere")]
+    #[case("x: out 1\nx: out 2")]
+    #[case("out 1 2")]
+    #[case(".d 4")]
+    // labels past address 7 do not fit into the operand
+    #[case("out 1\nout 1\nout 1\nout 1\nfar: out 1\njnz far")]
+    fn test_errors(#[case] source: &str) {
+        assert!(assemble(source).is_err());
+    }
+}
@@ -0,0 +1,31 @@
+use day_17::assembler::assemble;
+use miette::{miette, Context, IntoDiagnostic};
+use std::fs;
+
+/// Assembles a mnemonic source file and prints it in the puzzle input format,
+/// with `run` it also runs the program and prints its output.
+///
+/// `cargo run -p day-17 --bin assemble -- <source-file> [run]`
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let path = args
+        .next()
+        .ok_or_else(|| miette!("missing source file argument"))?;
+    let run = args.next().is_some_and(|arg| arg == "run");
+
+    let source = fs::read_to_string(&path)
+        .into_diagnostic()
+        .context(format!("read {path}"))?;
+    let assembly = assemble(&source)?;
+    println!("{}", assembly.to_input());
+
+    if run {
+        let mut computer = assembly.into_computer();
+        computer.run_with_limit(1_000_000)?;
+        println!("\noutput: {}", computer.output_string());
+    }
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use day_17::disassembler::listing;
+use day_17::Computer;
+use miette::{Context, IntoDiagnostic};
+
+/// Prints the disassembled program of `input.txt` and one register snapshot per executed instruction.
+/// Register A can be overridden, e.g. to trace the quine found in part 2.
+///
+/// `cargo run -p day-17 --bin trace -- [register-a] [max-steps]`
+#[tracing::instrument]
+fn main() -> miette::Result<()> {
+    tracing_subscriber::fmt::init();
+
+    let mut args = std::env::args().skip(1);
+    let register_a = args
+        .next()
+        .map(|a| a.parse::<u64>())
+        .transpose()
+        .into_diagnostic()
+        .context("parse register a")?;
+    let max_steps = match args.next() {
+        Some(max_steps) => max_steps
+            .parse()
+            .into_diagnostic()
+            .context("parse max steps")?,
+        None => 10_000,
+    };
+
+    let file = include_str!("../../input.txt");
+    let mut computer = Computer::parse(file)?;
+    if let Some(a) = register_a {
+        computer.registers.a = a;
+    }
+
+    println!("{}\n", listing(&computer.program)?);
+    for step in computer.trace(max_steps)? {
+        println!("{step}");
+    }
+    println!("\noutput: {}", computer.output_string());
+    Ok(())
+}
@@ -0,0 +1,429 @@
+use itertools::Itertools;
+use miette::miette;
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+use std::fmt::{Display, Formatter};
+use std::ops::BitXor;
+use tracing::debug;
+
+#[derive(TryFromPrimitive, IntoPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u64)]
+pub enum Instruction {
+    ///The adv instruction (opcode 0) performs division. The numerator is the value in the A register.
+    /// The denominator is found by raising 2 to the power of the instruction's combo operand.
+    /// (So, an operand of 2 would divide A by 4 (2^2); an operand of 5 would divide A by 2^B.)
+    /// The result of the division operation is truncated to an integer and then written to the A register.
+    Adv = 0,
+
+    ///The bxl instruction (opcode 1) calculates the bitwise XOR of register B
+    /// and the instruction's literal operand, then stores the result in register B.
+    Bxl = 1,
+
+    ///The bst instruction (opcode 2) calculates the value of its combo operand modulo 8
+    /// (thereby keeping only its lowest 3 bits), then writes that value to the B register.
+    Bst = 2,
+
+    ///The jnz instruction (opcode 3) does nothing if the A register is 0.
+    /// However, if the A register is not zero,
+    /// it jumps by setting the instruction pointer to the value of its literal operand;
+    /// if this instruction jumps, the instruction pointer is not increased by 2 after this instruction.
+    Jnz = 3,
+
+    ///The bxc instruction (opcode 4) calculates the bitwise XOR of register B and register C,
+    /// then stores the result in register B. (For legacy reasons,
+    /// this instruction reads an operand but ignores it.)
+    Bxc = 4,
+
+    ///The out instruction (opcode 5) calculates the value of its combo operand modulo 8,
+    /// then outputs that value.
+    /// (If a program outputs multiple values, they are separated by commas.)
+    Out = 5,
+
+    ///The bdv instruction (opcode 6) works exactly like the adv instruction except that the result is stored in the B register.
+    /// (The numerator is still read from the A register.)
+    Bdv = 6,
+
+    ///The cdv instruction (opcode 7) works exactly like the adv instruction except that the result is stored in the C register.
+    /// (The numerator is still read from the A register.)
+    Cdv = 7,
+}
+
+impl Instruction {
+    pub const ALL: [Instruction; 8] = [
+        Instruction::Adv,
+        Instruction::Bxl,
+        Instruction::Bst,
+        Instruction::Jnz,
+        Instruction::Bxc,
+        Instruction::Out,
+        Instruction::Bdv,
+        Instruction::Cdv,
+    ];
+
+    pub fn operand_type(&self) -> OperandType {
+        match self {
+            Instruction::Adv => OperandType::Combo,
+            Instruction::Bxl => OperandType::Literal,
+            Instruction::Bst => OperandType::Combo,
+            Instruction::Jnz => OperandType::Literal,
+            Instruction::Bxc => OperandType::Ignored,
+            Instruction::Out => OperandType::Combo,
+            Instruction::Bdv => OperandType::Combo,
+            Instruction::Cdv => OperandType::Combo,
+        }
+    }
+
+    pub fn mnemonic(&self) -> &'static str {
+        match self {
+            Instruction::Adv => "adv",
+            Instruction::Bxl => "bxl",
+            Instruction::Bst => "bst",
+            Instruction::Jnz => "jnz",
+            Instruction::Bxc => "bxc",
+            Instruction::Out => "out",
+            Instruction::Bdv => "bdv",
+            Instruction::Cdv => "cdv",
+        }
+    }
+
+    pub fn from_mnemonic(mnemonic: &str) -> Option<Instruction> {
+        Instruction::ALL
+            .into_iter()
+            .find(|instruction| instruction.mnemonic() == mnemonic)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OperandType {
+    Combo,
+    Literal,
+    Ignored,
+}
+
+/// The meaning of a 3-bit operand, depending on the [OperandType] of its instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand {
+    Literal(u64),
+    RegisterA,
+    RegisterB,
+    RegisterC,
+    /// combo operand 7 is reserved and will not appear in valid programs
+    Reserved,
+    Ignored(u64),
+}
+
+impl Operand {
+    pub fn decode(operand_type: OperandType, operand: u64) -> Operand {
+        match (operand_type, operand) {
+            (OperandType::Combo, 0..=3) => Operand::Literal(operand),
+            (OperandType::Combo, 4) => Operand::RegisterA,
+            (OperandType::Combo, 5) => Operand::RegisterB,
+            (OperandType::Combo, 6) => Operand::RegisterC,
+            (OperandType::Combo, _) => Operand::Reserved,
+            (OperandType::Literal, _) => Operand::Literal(operand),
+            (OperandType::Ignored, _) => Operand::Ignored(operand),
+        }
+    }
+}
+
+/// Combo registers are written as `a`, `b` and `c`, everything else as its number.
+impl Display for Operand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let operand = match self {
+            Operand::Literal(value) | Operand::Ignored(value) => value.to_string(),
+            Operand::RegisterA => "a".to_string(),
+            Operand::RegisterB => "b".to_string(),
+            Operand::RegisterC => "c".to_string(),
+            Operand::Reserved => "7".to_string(),
+        };
+        f.pad(&operand)
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Registers {
+    pub a: u64,
+    pub b: u64,
+    pub c: u64,
+}
+
+impl Display for Registers {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "a={} b={} c={}", self.a, self.b, self.c)
+    }
+}
+
+/// One executed instruction with the registers before and after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceStep {
+    pub instruction_pointer: usize,
+    pub instruction: Instruction,
+    pub operand: Operand,
+    pub before: Registers,
+    pub after: Registers,
+    pub output: Option<u64>,
+    /// where execution continues
+    pub next_instruction_pointer: usize,
+}
+
+impl Display for TraceStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{:>3}: {} {:<2} | {} -> {}",
+            self.instruction_pointer,
+            self.instruction.mnemonic(),
+            self.operand,
+            self.before,
+            self.after
+        )?;
+        if let Some(output) = self.output {
+            write!(f, " | out {output}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default, Clone, Debug, PartialEq, Eq)]
+pub struct Computer {
+    pub registers: Registers,
+    pub program: Vec<u64>,
+    pub instruction_pointer: usize,
+    pub output: Vec<u64>,
+}
+
+impl Computer {
+    pub fn new(registers: Registers, program: Vec<u64>) -> Self {
+        Computer {
+            registers,
+            program,
+            instruction_pointer: 0,
+            output: vec![],
+        }
+    }
+
+    /// Parses the puzzle input: three registers and the program.
+    pub fn parse(input: &str) -> miette::Result<Self> {
+        let (_, computer) =
+            crate::parse(input.trim()).map_err(|e| miette!("parse failed {}", e))?;
+        Ok(computer)
+    }
+
+    /// Starts the program again with the given registers.
+    pub fn reset(&mut self, registers: Registers) {
+        self.registers = registers;
+        self.instruction_pointer = 0;
+        self.output = vec![];
+    }
+
+    /// If the computer tries to read an opcode past the end of the program, it instead halts.
+    pub fn is_halted(&self) -> bool {
+        self.instruction_pointer >= self.program.len()
+    }
+
+    pub fn output_string(&self) -> String {
+        self.output.iter().join(",")
+    }
+
+    fn resolve(&self, operand: Operand) -> miette::Result<u64> {
+        match operand {
+            Operand::Literal(value) | Operand::Ignored(value) => Ok(value),
+            Operand::RegisterA => Ok(self.registers.a),
+            Operand::RegisterB => Ok(self.registers.b),
+            Operand::RegisterC => Ok(self.registers.c),
+            Operand::Reserved => Err(miette!(
+                "reserved combo operand 7 at {}",
+                self.instruction_pointer + 1
+            )),
+        }
+    }
+
+    /// The denominator `2^x` of the `*dv` instructions, shifts of 64 and more leave 0.
+    fn divide_a(&self, operand: Operand) -> miette::Result<u64> {
+        let shift = self.resolve(operand)?;
+        Ok(u32::try_from(shift)
+            .ok()
+            .and_then(|shift| self.registers.a.checked_shr(shift))
+            .unwrap_or(0))
+    }
+
+    /// Executes the instruction at the instruction pointer, `None` if the computer already halted.
+    pub fn step(&mut self) -> miette::Result<Option<TraceStep>> {
+        if self.is_halted() {
+            return Ok(None);
+        }
+        let instruction_pointer = self.instruction_pointer;
+        let instruction =
+            Instruction::try_from(self.program[instruction_pointer]).map_err(|_| {
+                miette!(
+                    "invalid opcode {} at {instruction_pointer}",
+                    self.program[instruction_pointer]
+                )
+            })?;
+        let raw_operand = *self.program.get(instruction_pointer + 1).ok_or_else(|| {
+            miette!("missing operand for {instruction:?} at {instruction_pointer}")
+        })?;
+        let operand = Operand::decode(instruction.operand_type(), raw_operand);
+
+        let before = self.registers;
+        let mut output = None;
+        let mut next_instruction_pointer = instruction_pointer + 2;
+        match instruction {
+            Instruction::Adv => self.registers.a = self.divide_a(operand)?,
+            Instruction::Bxl => self.registers.b = self.registers.b.bitxor(raw_operand),
+            Instruction::Bst => self.registers.b = self.resolve(operand)? % 8,
+            Instruction::Jnz => {
+                if self.registers.a != 0 {
+                    next_instruction_pointer = raw_operand as usize;
+                }
+            }
+            Instruction::Bxc => self.registers.b = self.registers.b.bitxor(self.registers.c),
+            Instruction::Out => {
+                let value = self.resolve(operand)? % 8;
+                self.output.push(value);
+                output = Some(value);
+            }
+            Instruction::Bdv => self.registers.b = self.divide_a(operand)?,
+            Instruction::Cdv => self.registers.c = self.divide_a(operand)?,
+        }
+        self.instruction_pointer = next_instruction_pointer;
+
+        let step = TraceStep {
+            instruction_pointer,
+            instruction,
+            operand,
+            before,
+            after: self.registers,
+            output,
+            next_instruction_pointer,
+        };
+        debug!("{step}");
+        Ok(Some(step))
+    }
+
+    /// Runs until the program halts and returns the number of executed instructions.
+    /// Fails if it is still running after `max_steps` instructions.
+    pub fn run_with_limit(&mut self, max_steps: usize) -> miette::Result<usize> {
+        for steps in 0..max_steps {
+            if self.step()?.is_none() {
+                return Ok(steps);
+            }
+        }
+        if self.is_halted() {
+            Ok(max_steps)
+        } else {
+            Err(miette!(
+                "program did not halt within {max_steps} steps (instruction pointer {})",
+                self.instruction_pointer
+            ))
+        }
+    }
+
+    /// Runs until the program halts, which might be never.
+    pub fn run(&mut self) -> miette::Result<()> {
+        self.run_with_limit(usize::MAX).map(|_| ())
+    }
+
+    /// Like [Computer::run_with_limit] but keeps a register snapshot of every instruction.
+    pub fn trace(&mut self, max_steps: usize) -> miette::Result<Vec<TraceStep>> {
+        let mut steps = vec![];
+        loop {
+            if steps.len() >= max_steps && !self.is_halted() {
+                return Err(miette!(
+                    "program did not halt within {max_steps} steps (instruction pointer {})",
+                    self.instruction_pointer
+                ));
+            }
+            match self.step()? {
+                Some(step) => steps.push(step),
+                None => return Ok(steps),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace() -> miette::Result<()> {
+        let mut computer = Computer::new(
+            Registers {
+                a: 10,
+                ..Registers::default()
+            },
+            vec![0, 1, 5, 4, 3, 0],
+        );
+        let trace = computer.trace(100)?;
+
+        // a = 10 -> 5 -> 2 -> 1 -> 0, three instructions per loop
+        assert_eq!(12, trace.len());
+        assert_eq!(
+            TraceStep {
+                instruction_pointer: 0,
+                instruction: Instruction::Adv,
+                operand: Operand::Literal(1),
+                before: Registers {
+                    a: 10,
+                    ..Registers::default()
+                },
+                after: Registers {
+                    a: 5,
+                    ..Registers::default()
+                },
+                output: None,
+                next_instruction_pointer: 2,
+            },
+            trace[0]
+        );
+        assert_eq!(
+            "  2: out a  | a=5 b=0 c=0 -> a=5 b=0 c=0 | out 5",
+            trace[1].to_string()
+        );
+        assert_eq!(0, trace[2].next_instruction_pointer);
+        assert_eq!(6, trace.last().unwrap().next_instruction_pointer);
+        assert_eq!("5,2,1,0", computer.output_string());
+        Ok(())
+    }
+
+    #[test]
+    fn test_adv_with_register_operand() -> miette::Result<()> {
+        // a = a / 2^b
+        let mut computer = Computer::new(Registers { a: 64, b: 3, c: 0 }, vec![0, 5]);
+        computer.run()?;
+        assert_eq!(8, computer.registers.a);
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_limit() {
+        // jnz 0 with a != 0 loops forever
+        let mut computer = Computer::new(
+            Registers {
+                a: 1,
+                ..Registers::default()
+            },
+            vec![3, 0],
+        );
+        assert!(computer.run_with_limit(1000).is_err());
+        assert!(computer.clone().trace(10).is_err());
+        assert!(computer.clone().trace(0).is_err());
+        assert!(computer.clone().run_with_limit(0).is_err());
+
+        computer.reset(Registers::default());
+        assert_eq!(1, computer.run_with_limit(1).unwrap());
+        // an empty trace of a program that already halted
+        assert_eq!(0, computer.trace(0).unwrap().len());
+    }
+
+    #[test]
+    fn test_invalid_programs() {
+        assert!(Computer::new(Registers::default(), vec![5, 7])
+            .run()
+            .is_err());
+        assert!(Computer::new(Registers::default(), vec![8, 0])
+            .run()
+            .is_err());
+        assert!(Computer::new(Registers::default(), vec![5]).run().is_err());
+    }
+}
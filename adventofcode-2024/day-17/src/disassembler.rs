@@ -0,0 +1,115 @@
+use crate::computer::{Instruction, Operand};
+use miette::miette;
+use std::fmt::{Display, Formatter};
+
+/// One instruction of a program, decoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DisassembledInstruction {
+    pub address: usize,
+    pub instruction: Instruction,
+    pub operand: Operand,
+}
+
+impl DisassembledInstruction {
+    /// What the instruction does, e.g. `c = a >> b`.
+    pub fn pseudo_code(&self) -> String {
+        let operand = self.operand;
+        match self.instruction {
+            Instruction::Adv => format!("a = a >> {operand}"),
+            Instruction::Bxl => format!("b = b ^ {operand}"),
+            Instruction::Bst => format!("b = {operand} % 8"),
+            Instruction::Jnz => format!("if a != 0 goto {operand}"),
+            Instruction::Bxc => "b = b ^ c".to_string(),
+            Instruction::Out => format!("out {operand} % 8"),
+            Instruction::Bdv => format!("b = a >> {operand}"),
+            Instruction::Cdv => format!("c = a >> {operand}"),
+        }
+    }
+}
+
+/// `mnemonic operand ; address: pseudo code`
+impl Display for DisassembledInstruction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let assembly = format!("{} {}", self.instruction.mnemonic(), self.operand);
+        write!(
+            f,
+            "{assembly:<7} ; {:>2}: {}",
+            self.address,
+            self.pseudo_code()
+        )
+    }
+}
+
+/// Decodes a program from the start, two numbers per instruction.
+pub fn disassemble(program: &[u64]) -> miette::Result<Vec<DisassembledInstruction>> {
+    if !program.len().is_multiple_of(2) {
+        return Err(miette!(
+            "program has an odd length of {}, the last opcode has no operand",
+            program.len()
+        ));
+    }
+
+    program
+        .chunks(2)
+        .enumerate()
+        .map(|(idx, chunk)| {
+            let address = idx * 2;
+            let instruction = Instruction::try_from(chunk[0])
+                .map_err(|_| miette!("invalid opcode {} at {address}", chunk[0]))?;
+            if chunk[1] > 7 {
+                return Err(miette!(
+                    "operand {} at {} is not 3-bit",
+                    chunk[1],
+                    address + 1
+                ));
+            }
+            Ok(DisassembledInstruction {
+                address,
+                instruction,
+                operand: Operand::decode(instruction.operand_type(), chunk[1]),
+            })
+        })
+        .collect()
+}
+
+/// The listing of a whole program, one instruction per line.
+pub fn listing(program: &[u64]) -> miette::Result<String> {
+    Ok(disassemble(program)?
+        .iter()
+        .map(|instruction| instruction.to_string())
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_listing() -> miette::Result<()> {
+        let expected = r#"
+bst a   ;  0: b = a % 8
+bxl 5   ;  2: b = b ^ 5
+cdv b   ;  4: c = a >> b
+bxl 6   ;  6: b = b ^ 6
+adv 3   ;  8: a = a >> 3
+bxc 6   ; 10: b = b ^ c
+out b   ; 12: out b % 8
+jnz 0   ; 14: if a != 0 goto 0
+        "#
+        .trim();
+        assert_eq!(
+            expected,
+            listing(&[2, 4, 1, 5, 7, 5, 1, 6, 0, 3, 4, 6, 5, 5, 3, 0])?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_invalid_programs() {
+        assert!(disassemble(&[0, 3, 5]).is_err());
+        assert!(disassemble(&[9, 3]).is_err());
+        assert!(disassemble(&[0, 8]).is_err());
+        assert_eq!(Operand::Reserved, disassemble(&[5, 7]).unwrap()[0].operand);
+    }
+}
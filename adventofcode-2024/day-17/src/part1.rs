@@ -1,21 +1,14 @@
-use crate::parse;
-use itertools::Itertools;
-use miette::miette;
+use crate::Computer;
 use tracing::debug;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, mut computer) = parse(input.trim()).map_err(|e| miette!("parse failed {}", e))?;
+    let mut computer = Computer::parse(input)?;
 
     debug!("Input: \n{input}\n\nInitial_state: \n{computer:?}");
 
-    computer.run();
-    let output = computer
-        .output
-        .into_iter()
-        .map(|num| num.to_string())
-        .collect_vec()
-        .join(",");
+    computer.run()?;
+    let output = computer.output_string();
 
     debug!("Output: {}", output);
     Ok(output)
@@ -51,9 +44,9 @@ Program: 2,6
         "#
         .trim();
 
-        let mut computer = parse(input).unwrap().1;
-        computer.run();
-        assert_eq!(computer.register_b, 1);
+        let mut computer = Computer::parse(input)?;
+        computer.run()?;
+        assert_eq!(computer.registers.b, 1);
         Ok(())
     }
     #[test]
@@ -68,8 +61,8 @@ Program: 5,0,5,1,5,4
         "#
         .trim();
 
-        let mut computer = parse(input).unwrap().1;
-        computer.run();
+        let mut computer = Computer::parse(input)?;
+        computer.run()?;
 
         assert_eq!(computer.output, vec![0, 1, 2]);
         Ok(())
@@ -87,11 +80,11 @@ Program: 0,1,5,4,3,0
         "#
         .trim();
 
-        let mut computer = parse(input).unwrap().1;
-        computer.run();
+        let mut computer = Computer::parse(input)?;
+        computer.run()?;
 
         assert_eq!(computer.output, vec![4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0]);
-        assert_eq!(computer.register_a, 0);
+        assert_eq!(computer.registers.a, 0);
         Ok(())
     }
 
@@ -107,10 +100,10 @@ Program: 1,7
         "#
         .trim();
 
-        let mut computer = parse(input).unwrap().1;
-        computer.run();
+        let mut computer = Computer::parse(input)?;
+        computer.run()?;
 
-        assert_eq!(computer.register_b, 26);
+        assert_eq!(computer.registers.b, 26);
         Ok(())
     }
 
@@ -126,10 +119,10 @@ Program: 4,0
         "#
         .trim();
 
-        let mut computer = parse(input).unwrap().1;
-        computer.run();
+        let mut computer = Computer::parse(input)?;
+        computer.run()?;
 
-        assert_eq!(computer.register_b, 44354);
+        assert_eq!(computer.registers.b, 44354);
         Ok(())
     }
 }
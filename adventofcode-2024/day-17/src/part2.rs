@@ -1,59 +1,17 @@
-use crate::{parse, Computer};
-use itertools::Itertools;
-use miette::miette;
-use std::collections::HashSet;
-use tracing::{debug, info};
+use crate::quine::QuineSolver;
+use crate::Computer;
+use tracing::info;
 
 #[tracing::instrument(skip(input))]
 pub fn process(input: &str) -> miette::Result<String> {
-    let (_, computer) = parse(input.trim()).map_err(|e| miette!("parse failed {}", e))?;
+    let computer = Computer::parse(input)?;
 
     info!("Input: \n{input}\n\nInitial_state: \n{computer:?}");
 
-    let a = find_a(computer);
+    let quine = QuineSolver::default().solve(&computer)?;
 
-    info!("a: {a}");
-    Ok(a.to_string())
-}
-
-fn find_a(computer: Computer) -> u64 {
-    let mut candidates: Vec<u64> = vec![0];
-    let mut seen = HashSet::new();
-
-    let mut c = computer.clone();
-    while !candidates.is_empty() {
-        let candidate = candidates.remove(0);
-
-        for x in 0..8 {
-            let a = candidate * 8 + x;
-            if seen.contains(&a) {
-                continue;
-            } else {
-                seen.insert(a);
-            }
-            debug!("find_a: a: {a}");
-
-            c.reset();
-            c.register_a = a;
-
-            c.run();
-
-            let output = c.output.clone();
-
-            if output == computer.program {
-                return a;
-            }
-            let num_digits = output.len();
-            let last_elements = &c.program[&c.program.len() - num_digits..];
-
-            if last_elements == output {
-                candidates.push(a);
-            }
-        }
-        candidates.sort();
-    }
-
-    panic!("Couldn't find solution")
+    info!("a: {} ({:?})", quine.a, quine.strategy);
+    Ok(quine.a.to_string())
 }
 
 #[cfg(test)]
@@ -88,8 +46,6 @@ Program: 2,4,1,5,7,5,1,6,0,3,4,6,5,5,3,0
         "#
         .trim();
 
-        let (_, computer) = parse(input.trim()).map_err(|e| miette!("parse failed {}", e))?;
-
         assert_eq!(process(input)?, "136904920099226");
 
         Ok(())
@@ -0,0 +1,232 @@
+use crate::computer::{Computer, Instruction, Operand, Registers};
+use crate::disassembler::disassemble;
+use miette::miette;
+use tracing::{debug, info};
+
+/// The shape of program that makes the digit by digit search possible:
+/// a single loop ending in `jnz 0` that outputs one value per iteration,
+/// shifts `a` right by a constant and overwrites `b` and `c` before reading them.
+/// Every iteration then only depends on the remaining bits of `a`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopStructure {
+    /// bits removed from `a` per iteration
+    pub shift: u32,
+}
+
+/// Checks whether `program` has a [LoopStructure].
+pub fn analyse_loop(program: &[u64]) -> Option<LoopStructure> {
+    let instructions = disassemble(program).ok()?;
+    let (last, body) = instructions.split_last()?;
+    if last.instruction != Instruction::Jnz || last.operand != Operand::Literal(0) {
+        return None;
+    }
+
+    let mut shift = None;
+    let mut outputs = 0;
+    let mut b_written = false;
+    let mut c_written = false;
+    for instruction in body {
+        let reads_b = matches!(instruction.instruction, Instruction::Bxl | Instruction::Bxc)
+            || instruction.operand == Operand::RegisterB;
+        let reads_c = instruction.instruction == Instruction::Bxc
+            || instruction.operand == Operand::RegisterC;
+        if (reads_b && !b_written) || (reads_c && !c_written) {
+            return None;
+        }
+
+        match (instruction.instruction, instruction.operand) {
+            (_, Operand::Reserved) | (Instruction::Jnz, _) => return None,
+            (Instruction::Adv, Operand::Literal(bits @ 1..=3)) if shift.is_none() => {
+                shift = Some(bits as u32)
+            }
+            (Instruction::Adv, _) => return None,
+            (Instruction::Out, _) => outputs += 1,
+            (Instruction::Bst | Instruction::Bxl | Instruction::Bxc | Instruction::Bdv, _) => {
+                b_written = true
+            }
+            (Instruction::Cdv, _) => c_written = true,
+        }
+    }
+
+    (outputs == 1).then_some(LoopStructure { shift: shift? })
+}
+
+/// How a [Quine] was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// `a` was built from its most significant bits down, see [LoopStructure]
+    LoopStructure(LoopStructure),
+    /// every `a` was tried until one worked, stopping a run at its first wrong output
+    ConstraintSearch,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Quine {
+    pub a: u64,
+    pub strategy: Strategy,
+}
+
+/// Finds the lowest positive value for register `a` that makes a program output itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuineSolver {
+    /// instructions a single run may take before it counts as not terminating
+    pub max_steps: usize,
+    /// the largest `a` the constraint search tries
+    pub search_limit: u64,
+}
+
+impl Default for QuineSolver {
+    fn default() -> Self {
+        QuineSolver {
+            max_steps: 100_000,
+            search_limit: 1 << 24,
+        }
+    }
+}
+
+impl QuineSolver {
+    pub fn solve(&self, computer: &Computer) -> miette::Result<Quine> {
+        match analyse_loop(&computer.program) {
+            Some(structure) => {
+                info!("program is a simple loop shifting a by {}", structure.shift);
+                Ok(Quine {
+                    a: self.loop_search(computer, structure)?,
+                    strategy: Strategy::LoopStructure(structure),
+                })
+            }
+            None => {
+                info!("program is no simple loop, falling back to the constraint search");
+                Ok(Quine {
+                    a: self.constraint_search(computer)?,
+                    strategy: Strategy::ConstraintSearch,
+                })
+            }
+        }
+    }
+
+    fn registers_with_a(computer: &Computer, a: u64) -> Registers {
+        Registers {
+            a,
+            ..computer.registers
+        }
+    }
+
+    /// The last iteration only sees the top bits of `a`, so candidates for
+    /// the top bits are extended by `shift` bits for every earlier output.
+    /// All candidates are kept because a smaller prefix can die out later.
+    fn loop_search(&self, computer: &Computer, structure: LoopStructure) -> miette::Result<u64> {
+        let program = &computer.program;
+        let mut candidates = vec![0u64];
+        let mut run = computer.clone();
+
+        for idx in (0..program.len()).rev() {
+            let mut next_candidates = vec![];
+            for candidate in &candidates {
+                for low_bits in 0..1u64 << structure.shift {
+                    let Some(a) = candidate
+                        .checked_shl(structure.shift)
+                        .filter(|shifted| shifted >> structure.shift == *candidate)
+                        .map(|shifted| shifted | low_bits)
+                    else {
+                        continue;
+                    };
+                    run.reset(Self::registers_with_a(computer, a));
+                    if run.run_with_limit(self.max_steps).is_ok() && run.output == program[idx..] {
+                        next_candidates.push(a);
+                    }
+                }
+            }
+            debug!("{} candidates for output {idx}", next_candidates.len());
+            next_candidates.sort();
+            next_candidates.dedup();
+            candidates = next_candidates;
+        }
+
+        candidates
+            .into_iter()
+            .find(|a| *a > 0)
+            .ok_or_else(|| miette!("no value for register a makes the program output itself"))
+    }
+
+    /// Tries every `a` up to the search limit.
+    pub fn constraint_search(&self, computer: &Computer) -> miette::Result<u64> {
+        let program = &computer.program;
+        let mut run = computer.clone();
+
+        'candidates: for a in 1..=self.search_limit {
+            run.reset(Self::registers_with_a(computer, a));
+            for _ in 0..self.max_steps {
+                let Ok(Some(step)) = run.step() else {
+                    break;
+                };
+                if let Some(value) = step.output {
+                    if program.get(run.output.len() - 1) != Some(&value) {
+                        continue 'candidates;
+                    }
+                }
+            }
+            if run.is_halted() && run.output == *program {
+                return Ok(a);
+            }
+        }
+
+        Err(miette!(
+            "no value for register a up to {} makes the program output itself",
+            self.search_limit
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case(&[0, 3, 5, 4, 3, 0], Some(3))]
+    #[case(&[2, 4, 1, 5, 7, 5, 1, 6, 0, 3, 4, 6, 5, 5, 3, 0], Some(3))]
+    #[case(&[0, 1, 5, 4, 3, 0], Some(1))]
+    // b is read before it is written
+    #[case(&[1, 0, 0, 3, 5, 4, 3, 0], None)]
+    // a is shifted by a register
+    #[case(&[0, 5, 5, 4, 3, 0], None)]
+    // two outputs per iteration
+    #[case(&[0, 3, 5, 4, 5, 4, 3, 0], None)]
+    // no loop
+    #[case(&[0, 3, 5, 4], None)]
+    // jumps somewhere else
+    #[case(&[0, 3, 5, 4, 3, 2], None)]
+    fn test_analyse_loop(#[case] program: &[u64], #[case] shift: Option<u32>) {
+        assert_eq!(
+            shift.map(|shift| LoopStructure { shift }),
+            analyse_loop(program)
+        );
+    }
+
+    #[test]
+    fn test_solve_example() -> miette::Result<()> {
+        let computer = Computer::new(Registers::default(), vec![0, 3, 5, 4, 3, 0]);
+        let solver = QuineSolver::default();
+
+        let quine = solver.solve(&computer)?;
+        assert_eq!(117440, quine.a);
+        assert_eq!(
+            Strategy::LoopStructure(LoopStructure { shift: 3 }),
+            quine.strategy
+        );
+        assert_eq!(117440, solver.constraint_search(&computer)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_solve_without_quine() {
+        // only ever outputs 0
+        let computer = Computer::new(Registers::default(), vec![0, 3, 5, 0, 3, 0]);
+        let solver = QuineSolver {
+            search_limit: 1000,
+            ..QuineSolver::default()
+        };
+        assert!(solver.solve(&computer).is_err());
+        assert!(solver.constraint_search(&computer).is_err());
+    }
+}
@@ -1,3 +1,4 @@
+use day_18::memory::{MemorySpace, PUZZLE_GRID};
 use day_18::*;
 
 fn main() {
@@ -7,15 +8,22 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    part1::process(
-        divan::black_box(include_str!("../input.txt",)),
-        &(0..=70),
-        1024,
-    )
-    .unwrap();
+    part1::process(divan::black_box(include_str!("../input.txt",))).unwrap();
 }
 
 #[divan::bench]
 fn part2() {
-    part2::process(divan::black_box(include_str!("../input.txt",)), &(0..=70)).unwrap();
+    part2::process(divan::black_box(include_str!("../input.txt",))).unwrap();
+}
+
+#[divan::bench]
+fn blocking_byte_binary_search(bencher: divan::Bencher) {
+    let memory = MemorySpace::parse(include_str!("../input.txt"), &PUZZLE_GRID).unwrap();
+    bencher.bench(|| divan::black_box(&memory).first_blocking_byte_binary_search());
+}
+
+#[divan::bench]
+fn blocking_byte_union_find(bencher: divan::Bencher) {
+    let memory = MemorySpace::parse(include_str!("../input.txt"), &PUZZLE_GRID).unwrap();
+    bencher.bench(|| divan::black_box(&memory).first_blocking_byte_union_find());
 }
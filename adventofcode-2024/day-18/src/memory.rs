@@ -0,0 +1,336 @@
+use glam::IVec2;
+use miette::miette;
+use std::collections::VecDeque;
+use std::ops::RangeInclusive;
+
+/// Grid of the example, 7x7.
+pub const EXAMPLE_GRID: RangeInclusive<i32> = 0..=6;
+/// Grid of the real puzzle, 71x71.
+pub const PUZZLE_GRID: RangeInclusive<i32> = 0..=70;
+
+const NEIGHBORS: [IVec2; 4] = [IVec2::NEG_Y, IVec2::X, IVec2::Y, IVec2::NEG_X];
+
+/// The byte that cuts the exit off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockingByte {
+    /// 0 for the first byte that falls
+    pub index: usize,
+    pub position: IVec2,
+}
+
+/// The memory grid together with the time every cell gets corrupted.
+/// Walking starts at the top left corner, the exit is the bottom right corner.
+#[derive(Debug, Clone)]
+pub struct MemorySpace {
+    offset: i32,
+    size: i32,
+    bytes: Vec<IVec2>,
+    /// index of the first byte falling onto the cell, `usize::MAX` if none does
+    fall_index: Vec<usize>,
+}
+
+impl MemorySpace {
+    pub fn new(grid_limit: &RangeInclusive<i32>, bytes: Vec<IVec2>) -> miette::Result<Self> {
+        let offset = *grid_limit.start();
+        let size = grid_limit.end() - offset + 1;
+        if size <= 0 {
+            return Err(miette!("empty grid {grid_limit:?}"));
+        }
+
+        let mut fall_index = vec![usize::MAX; (size * size) as usize];
+        for (index, byte) in bytes.iter().enumerate() {
+            if !(grid_limit.contains(&byte.x) && grid_limit.contains(&byte.y)) {
+                return Err(miette!(
+                    "byte {index} at {byte} is outside of {grid_limit:?}"
+                ));
+            }
+            let cell = ((byte.y - offset) * size + byte.x - offset) as usize;
+            fall_index[cell] = fall_index[cell].min(index);
+        }
+
+        Ok(MemorySpace {
+            offset,
+            size,
+            bytes,
+            fall_index,
+        })
+    }
+
+    pub fn parse(input: &str, grid_limit: &RangeInclusive<i32>) -> miette::Result<Self> {
+        let (_, bytes) = crate::parse(input.trim()).map_err(|e| miette!("parse failed {}", e))?;
+        MemorySpace::new(grid_limit, bytes)
+    }
+
+    pub fn bytes(&self) -> &[IVec2] {
+        &self.bytes
+    }
+
+    fn start(&self) -> usize {
+        0
+    }
+
+    fn exit(&self) -> usize {
+        self.fall_index.len() - 1
+    }
+
+    fn is_corrupted(&self, cell: usize, bytes_fallen: usize) -> bool {
+        self.fall_index[cell] < bytes_fallen
+    }
+
+    fn neighbors(&self, cell: usize) -> impl Iterator<Item = usize> + '_ {
+        let pos = IVec2::new(cell as i32 % self.size, cell as i32 / self.size);
+        NEIGHBORS.iter().filter_map(move |offset| {
+            let next = pos + offset;
+            (next.cmpge(IVec2::ZERO).all() && next.cmplt(IVec2::splat(self.size)).all())
+                .then_some((next.y * self.size + next.x) as usize)
+        })
+    }
+
+    /// Breadth-first search from the start, returns the cells of one shortest path to the exit.
+    fn shortest_path(&self, bytes_fallen: usize) -> Option<Vec<usize>> {
+        if self.is_corrupted(self.start(), bytes_fallen) {
+            return None;
+        }
+        let mut previous = vec![usize::MAX; self.fall_index.len()];
+        previous[self.start()] = self.start();
+        let mut queue = VecDeque::from([self.start()]);
+
+        while let Some(cell) = queue.pop_front() {
+            if cell == self.exit() {
+                let mut path = vec![cell];
+                while *path.last().unwrap() != self.start() {
+                    path.push(previous[*path.last().unwrap()]);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for next in self.neighbors(cell) {
+                if previous[next] == usize::MAX && !self.is_corrupted(next, bytes_fallen) {
+                    previous[next] = cell;
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+
+    /// Number of steps to the exit after the first `bytes_fallen` bytes have fallen.
+    pub fn shortest_path_len(&self, bytes_fallen: usize) -> Option<u32> {
+        self.shortest_path(bytes_fallen)
+            .map(|path| path.len() as u32 - 1)
+    }
+
+    /// The shortest path length for every number of fallen bytes from 0 to all of them.
+    /// The search only runs again when a byte lands on the current path.
+    pub fn path_lengths(&self) -> Vec<Option<u32>> {
+        let mut lengths = Vec::with_capacity(self.bytes.len() + 1);
+        let mut on_path = vec![false; self.fall_index.len()];
+        let mut path = self.shortest_path(0);
+        path.iter().flatten().for_each(|cell| on_path[*cell] = true);
+
+        for bytes_fallen in 0..=self.bytes.len() {
+            if bytes_fallen > 0 && path.is_some() {
+                let byte = self.bytes[bytes_fallen - 1] - IVec2::splat(self.offset);
+                if on_path[(byte.y * self.size + byte.x) as usize] {
+                    path = self.shortest_path(bytes_fallen);
+                    on_path.fill(false);
+                    path.iter().flatten().for_each(|cell| on_path[*cell] = true);
+                }
+            }
+            lengths.push(path.as_ref().map(|path| path.len() as u32 - 1));
+        }
+        lengths
+    }
+
+    fn blocking_byte(&self, index: usize) -> BlockingByte {
+        BlockingByte {
+            index,
+            position: self.bytes[index],
+        }
+    }
+
+    /// Binary search over the number of fallen bytes, one breadth-first search per step.
+    pub fn first_blocking_byte_binary_search(&self) -> Option<BlockingByte> {
+        if self.shortest_path(self.bytes.len()).is_some() {
+            return None;
+        }
+        // smallest number of fallen bytes without a path
+        let mut low = 0;
+        let mut high = self.bytes.len();
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.shortest_path(mid).is_some() {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+        (low > 0).then(|| self.blocking_byte(low - 1))
+    }
+
+    /// Starts with all bytes fallen and removes them again in reverse order,
+    /// joining every freed cell with its free neighbors until start and exit are connected.
+    pub fn first_blocking_byte_union_find(&self) -> Option<BlockingByte> {
+        let all_fallen = self.bytes.len();
+        let mut sets = UnionFind::new(self.fall_index.len());
+        let free_cells = (0..self.fall_index.len())
+            .filter(|cell| !self.is_corrupted(*cell, all_fallen))
+            .collect::<Vec<_>>();
+        for cell in free_cells {
+            self.join_free_neighbors(&mut sets, cell, all_fallen);
+        }
+
+        let is_connected = |sets: &mut UnionFind, bytes_fallen: usize| {
+            !self.is_corrupted(self.start(), bytes_fallen)
+                && !self.is_corrupted(self.exit(), bytes_fallen)
+                && sets.find(self.start()) == sets.find(self.exit())
+        };
+        if is_connected(&mut sets, all_fallen) {
+            return None;
+        }
+
+        for index in (0..all_fallen).rev() {
+            let byte = self.bytes[index] - IVec2::splat(self.offset);
+            let cell = (byte.y * self.size + byte.x) as usize;
+            // a later duplicate of a byte does not free its cell
+            if self.fall_index[cell] == index {
+                self.join_free_neighbors(&mut sets, cell, index);
+            }
+            if is_connected(&mut sets, index) {
+                return Some(self.blocking_byte(index));
+            }
+        }
+        None
+    }
+
+    fn join_free_neighbors(&self, sets: &mut UnionFind, cell: usize, bytes_fallen: usize) {
+        for next in self.neighbors(cell) {
+            if !self.is_corrupted(next, bytes_fallen) {
+                sets.union(cell, next);
+            }
+        }
+    }
+}
+
+/// Disjoint sets with path halving and union by size.
+struct UnionFind {
+    parent: Vec<usize>,
+    size: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(len: usize) -> Self {
+        UnionFind {
+            parent: (0..len).collect(),
+            size: vec![1; len],
+        }
+    }
+
+    fn find(&mut self, mut element: usize) -> usize {
+        while self.parent[element] != element {
+            self.parent[element] = self.parent[self.parent[element]];
+            element = self.parent[element];
+        }
+        element
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a == b {
+            return;
+        }
+        let (small, large) = if self.size[a] < self.size[b] {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        self.parent[small] = large;
+        self.size[large] += self.size[small];
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "5,4
+4,2
+4,5
+3,0
+2,1
+6,3
+2,4
+1,5
+0,6
+3,3
+2,6
+5,1
+1,2
+5,5
+2,5
+6,5
+1,4
+0,4
+6,4
+1,1
+6,1
+1,0
+0,5
+1,6
+2,0";
+
+    #[test]
+    fn test_shortest_path_len() -> miette::Result<()> {
+        let memory = MemorySpace::parse(EXAMPLE, &EXAMPLE_GRID)?;
+        assert_eq!(Some(12), memory.shortest_path_len(0));
+        assert_eq!(Some(22), memory.shortest_path_len(12));
+        assert_eq!(None, memory.shortest_path_len(memory.bytes().len()));
+
+        let lengths = memory.path_lengths();
+        assert_eq!(memory.bytes().len() + 1, lengths.len());
+        for (bytes_fallen, length) in lengths.iter().enumerate() {
+            assert_eq!(memory.shortest_path_len(bytes_fallen), *length);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_first_blocking_byte() -> miette::Result<()> {
+        let memory = MemorySpace::parse(EXAMPLE, &EXAMPLE_GRID)?;
+        let expected = Some(BlockingByte {
+            index: 20,
+            position: IVec2::new(6, 1),
+        });
+        assert_eq!(expected, memory.first_blocking_byte_binary_search());
+        assert_eq!(expected, memory.first_blocking_byte_union_find());
+        Ok(())
+    }
+
+    #[test]
+    fn test_puzzle_grid() -> miette::Result<()> {
+        // a wall across the grid at y = 35 with the gap at x = 70 closed last
+        let bytes = (0..=70).map(|x| IVec2::new(x, 35)).collect::<Vec<_>>();
+        let memory = MemorySpace::new(&PUZZLE_GRID, bytes)?;
+        assert_eq!(Some(140), memory.shortest_path_len(0));
+        assert_eq!(Some(140), memory.shortest_path_len(70));
+
+        let expected = Some(BlockingByte {
+            index: 70,
+            position: IVec2::new(70, 35),
+        });
+        assert_eq!(expected, memory.first_blocking_byte_binary_search());
+        assert_eq!(expected, memory.first_blocking_byte_union_find());
+        assert_eq!(Some(140), memory.path_lengths()[70]);
+        assert_eq!(None, memory.path_lengths()[71]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_never_blocked() -> miette::Result<()> {
+        let memory = MemorySpace::parse("1,1\n1,1\n3,3", &EXAMPLE_GRID)?;
+        assert_eq!(None, memory.first_blocking_byte_binary_search());
+        assert_eq!(None, memory.first_blocking_byte_union_find());
+        assert!(MemorySpace::parse("7,0", &EXAMPLE_GRID).is_err());
+        Ok(())
+    }
+}
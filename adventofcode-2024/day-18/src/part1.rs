@@ -1,17 +1,34 @@
-use crate::find_path;
-use glam::IVec2;
+use crate::memory::{MemorySpace, PUZZLE_GRID};
 use miette::miette;
+use nom::bytes::complete::tag;
 use nom::character::complete;
 use nom::character::complete::char;
-use nom::character::complete::line_ending;
-use nom::combinator::map;
-use nom::multi::separated_list1;
-use nom::sequence::separated_pair;
+use nom::sequence::tuple;
 use nom::IResult;
 use std::ops::RangeInclusive;
 
 pub fn process(input: &str) -> miette::Result<String> {
-    process_parameterized(input, &(0..=70), 1024)
+    process_parameterized(input, &PUZZLE_GRID, 1024)
+}
+
+/// e.g. `0..=6,12` for the grid limit and number of bytes of the example
+fn parse_args(args: &str) -> IResult<&str, (RangeInclusive<i32>, usize)> {
+    let (remaining, (range_from, _, range_to, _, num_bytes)) = tuple((
+        complete::i32,
+        tag("..="),
+        complete::i32,
+        char(','),
+        complete::u64,
+    ))(args)?;
+
+    Ok((remaining, (range_from..=range_to, num_bytes as usize)))
+}
+
+pub fn process_with_args(input: &str, args: &str) -> miette::Result<String> {
+    let (_, (grid_limit, num_bytes)) =
+        parse_args(args).map_err(|e| miette!("arg-parse failed {}", e))?;
+
+    process_parameterized(input, &grid_limit, num_bytes)
 }
 
 #[tracing::instrument]
@@ -20,29 +37,15 @@ pub fn process_parameterized(
     grid_limit: &RangeInclusive<i32>,
     num_bytes: usize,
 ) -> miette::Result<String> {
-    let (_, byte_locations) = parse(input).map_err(|e| miette!("parse failed {}", e))?;
-
-    let goal = IVec2::new(*grid_limit.end(), *grid_limit.end());
+    let memory = MemorySpace::parse(input, grid_limit)?;
 
-    let Some((_path, cost)) = find_path(&byte_locations, &goal, num_bytes, grid_limit) else {
-        panic!("No path found")
-    };
+    let cost = memory
+        .shortest_path_len(num_bytes)
+        .ok_or_else(|| miette!("no path after {num_bytes} bytes"))?;
 
     Ok(cost.to_string())
 }
 
-const NEIGHBORS: [IVec2; 4] = [IVec2::NEG_Y, IVec2::X, IVec2::Y, IVec2::NEG_X];
-
-fn parse(input: &str) -> IResult<&str, Vec<IVec2>> {
-    separated_list1(
-        line_ending,
-        map(
-            separated_pair(complete::i32, char(','), complete::i32),
-            |(x, y)| IVec2::new(x, y),
-        ),
-    )(input)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -78,6 +81,7 @@ mod tests {
         "#
         .trim();
         assert_eq!("22", process_parameterized(input, &(0..=6), 12)?);
+        assert_eq!("22", process_with_args(input, "0..=6,12")?);
         Ok(())
     }
 }
@@ -1,4 +1,4 @@
-use glam::IVec2;
+use crate::memory::{MemorySpace, PUZZLE_GRID};
 use miette::miette;
 use nom::bytes::complete::tag;
 use nom::character::complete;
@@ -7,7 +7,7 @@ use nom::IResult;
 use std::ops::RangeInclusive;
 
 pub fn process(input: &str) -> miette::Result<String> {
-    process_parameterized(input, &(0..=70))
+    process_parameterized(input, &PUZZLE_GRID)
 }
 
 fn parse_args(args: &str) -> IResult<&str, RangeInclusive<i32>> {
@@ -18,9 +18,9 @@ fn parse_args(args: &str) -> IResult<&str, RangeInclusive<i32>> {
 }
 
 pub fn process_with_args(input: &str, args: &str) -> miette::Result<String> {
-    let (_, min_savings_limit) = parse_args(args).map_err(|e| miette!("arg-parse failed {}", e))?;
+    let (_, grid_limit) = parse_args(args).map_err(|e| miette!("arg-parse failed {}", e))?;
 
-    Ok(process_parameterized(input, &min_savings_limit)?)
+    process_parameterized(input, &grid_limit)
 }
 
 #[tracing::instrument]
@@ -28,26 +28,16 @@ pub fn process_parameterized(
     input: &str,
     grid_limit: &RangeInclusive<i32>,
 ) -> miette::Result<String> {
-    let (_, byte_locations): (&str, Vec<IVec2>) =
-        crate::parse(input).map_err(|e| miette!("parse failed {}", e))?;
+    let memory = MemorySpace::parse(input, grid_limit)?;
 
-    let goal = IVec2::new(*grid_limit.end(), *grid_limit.end());
+    let blocking_byte = memory
+        .first_blocking_byte_union_find()
+        .ok_or_else(|| miette!("the exit stays reachable"))?;
 
-    let mut low = 0;
-    let mut high = byte_locations.len();
-
-    while low < high {
-        let mid = (low + high) / 2;
-        if crate::find_path(&byte_locations, &goal, mid + 1, grid_limit).is_some() {
-            low = mid + 1;
-        } else {
-            high = mid;
-        }
-    }
-
-    let result = byte_locations[low];
-
-    Ok(format!("{},{}", result.x, result.y).to_string())
+    Ok(format!(
+        "{},{}",
+        blocking_byte.position.x, blocking_byte.position.y
+    ))
 }
 
 #[cfg(test)]
@@ -1,5 +1,6 @@
 use nom::IResult;
 
+pub mod matcher;
 pub mod part1;
 pub mod part2;
 
@@ -9,7 +10,7 @@ struct ProblemSetup<'a> {
     towels: Vec<&'a str>,
 }
 
-fn parser(input: &str) -> IResult<&str, ProblemSetup> {
+fn parser(input: &str) -> IResult<&str, ProblemSetup<'_>> {
     use nom::bytes::complete::tag;
     use nom::character::complete::*;
     use nom::multi::separated_list1;
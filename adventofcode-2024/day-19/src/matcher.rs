@@ -0,0 +1,191 @@
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+struct TrieNode {
+    children: HashMap<u8, usize>,
+    /// a pattern ends at this node
+    is_pattern: bool,
+}
+
+/// All patterns in a trie, so every start position of a towel is matched against all patterns in one walk.
+///
+/// Holds no state between towels, so one matcher can be shared by any number of threads.
+#[derive(Debug)]
+pub struct TowelMatcher {
+    nodes: Vec<TrieNode>,
+}
+
+/// Everything the matcher knows about a single towel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TowelMatch<'t> {
+    /// number of different pattern sequences that make up the towel
+    pub arrangements: u64,
+    /// one of these sequences, if there is any
+    pub example: Option<Vec<&'t str>>,
+}
+
+impl TowelMatch<'_> {
+    pub fn is_possible(&self) -> bool {
+        self.example.is_some()
+    }
+}
+
+impl TowelMatcher {
+    pub fn new<'p>(patterns: impl IntoIterator<Item = &'p str>) -> Self {
+        let mut nodes = vec![TrieNode::default()];
+        for pattern in patterns {
+            let mut node = 0;
+            for stripe in pattern.bytes() {
+                node = match nodes[node].children.get(&stripe) {
+                    Some(child) => *child,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let child = nodes.len() - 1;
+                        nodes[node].children.insert(stripe, child);
+                        child
+                    }
+                };
+            }
+            nodes[node].is_pattern = true;
+        }
+        TowelMatcher { nodes }
+    }
+
+    /// The end positions (exclusive) of all patterns that match `towel` at `start`.
+    fn pattern_ends<'a>(
+        &'a self,
+        towel: &'a [u8],
+        start: usize,
+    ) -> impl Iterator<Item = usize> + 'a {
+        towel[start..]
+            .iter()
+            .scan(0, |node, stripe| {
+                *node = *self.nodes[*node].children.get(stripe)?;
+                Some(*node)
+            })
+            .enumerate()
+            .filter(|(_, node)| self.nodes[*node].is_pattern)
+            .map(move |(offset, _)| start + offset + 1)
+    }
+
+    /// Dynamic programming from the end of the towel:
+    /// the arrangements of `towel[start..]` are the sum of the arrangements after every pattern that matches at `start`.
+    pub fn analyse<'t>(&self, towel: &'t str) -> TowelMatch<'t> {
+        let stripes = towel.as_bytes();
+        let mut arrangements = vec![0u64; stripes.len() + 1];
+        // end of the first pattern in one possible arrangement of `towel[start..]`
+        let mut next_end = vec![None; stripes.len() + 1];
+        arrangements[stripes.len()] = 1;
+
+        for start in (0..stripes.len()).rev() {
+            for end in self.pattern_ends(stripes, start) {
+                if arrangements[end] > 0 {
+                    arrangements[start] = arrangements[start].saturating_add(arrangements[end]);
+                    next_end[start].get_or_insert(end);
+                }
+            }
+        }
+
+        let example = (arrangements[0] > 0).then(|| {
+            let mut patterns = vec![];
+            let mut start = 0;
+            while let Some(end) = next_end[start] {
+                patterns.push(&towel[start..end]);
+                start = end;
+            }
+            patterns
+        });
+
+        TowelMatch {
+            arrangements: arrangements[0],
+            example,
+        }
+    }
+
+    /// Number of different ways to arrange the patterns into `towel`.
+    /// Saturates at `u64::MAX`.
+    pub fn count(&self, towel: &str) -> u64 {
+        self.analyse(towel).arrangements
+    }
+
+    pub fn is_possible(&self, towel: &str) -> bool {
+        self.analyse(towel).is_possible()
+    }
+
+    /// One way to arrange the patterns into `towel`.
+    pub fn decomposition<'t>(&self, towel: &'t str) -> Option<Vec<&'t str>> {
+        self.analyse(towel).example
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const PATTERNS: [&str; 8] = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+
+    #[rstest]
+    #[case("brwrr", 2)]
+    #[case("bggr", 1)]
+    #[case("gbbr", 4)]
+    #[case("rrbgbr", 6)]
+    #[case("ubwu", 0)]
+    #[case("bwurrg", 1)]
+    #[case("brgr", 2)]
+    #[case("bbrgwb", 0)]
+    #[case("", 1)]
+    fn test_count(#[case] towel: &str, #[case] expected: u64) {
+        let matcher = TowelMatcher::new(PATTERNS);
+        assert_eq!(expected, matcher.count(towel));
+        assert_eq!(expected > 0, matcher.is_possible(towel));
+    }
+
+    #[rstest]
+    #[case("brwrr")]
+    #[case("bggr")]
+    #[case("gbbr")]
+    #[case("rrbgbr")]
+    #[case("bwurrg")]
+    #[case("brgr")]
+    fn test_decomposition(#[case] towel: &str) {
+        let matcher = TowelMatcher::new(PATTERNS);
+        let decomposition = matcher.decomposition(towel).expect("towel is possible");
+        assert_eq!(towel, decomposition.concat());
+        assert!(decomposition
+            .iter()
+            .all(|pattern| PATTERNS.contains(pattern)));
+    }
+
+    #[test]
+    fn test_impossible_towel_has_no_example() {
+        let matcher = TowelMatcher::new(PATTERNS);
+        assert_eq!(
+            TowelMatch {
+                arrangements: 0,
+                example: None
+            },
+            matcher.analyse("ubwu")
+        );
+    }
+
+    #[test]
+    fn test_independent_matchers_in_parallel() {
+        // the same towel with different patterns must not share any results
+        std::thread::scope(|scope| {
+            let handles = [(vec!["a"], 1), (vec!["a", "aa"], 89), (vec!["b"], 0)].map(
+                |(patterns, expected)| {
+                    scope.spawn(move || {
+                        let matcher = TowelMatcher::new(patterns);
+                        for _ in 0..100 {
+                            assert_eq!(expected, matcher.count("aaaaaaaaaa"));
+                        }
+                    })
+                },
+            );
+            for handle in handles {
+                handle.join().expect("matcher thread panicked");
+            }
+        });
+    }
+}
@@ -1,58 +1,22 @@
+use crate::matcher::TowelMatcher;
 use crate::parser;
-use itertools::Itertools;
 use miette::miette;
 
 #[tracing::instrument]
 pub fn process(_input: &str) -> miette::Result<String> {
     let (_, problem_setup) = parser(_input).map_err(|e| miette!("parse failed {}", e))?;
 
-    //dbg!(&problem_setup);
+    let matcher = TowelMatcher::new(problem_setup.tokens);
 
-    let sorted_tokes = problem_setup
-        .tokens
-        .iter()
-        .sorted_by_key(|token| token.len())
-        .rev()
-        .cloned()
-        .collect_vec();
-
-    let result = &problem_setup
+    let result = problem_setup
         .towels
         .iter()
-        .filter(|towel| match_towel(towel, &sorted_tokes))
+        .filter(|towel| matcher.is_possible(towel))
         .count();
 
     Ok(result.to_string())
 }
 
-#[tracing::instrument]
-fn match_towel(towel: &str, tokens: &Vec<&str>) -> bool {
-    match_towel_recurse(towel, tokens)
-}
-
-#[tracing::instrument]
-fn match_towel_recurse(towel: &str, tokens: &Vec<&str>) -> bool {
-    if towel.is_empty() {
-        return true;
-    }
-    let matching_tokens = tokens
-        .iter()
-        .filter(|token| towel.starts_with(**token))
-        .collect_vec();
-
-    if matching_tokens.is_empty() {
-        return false;
-    }
-
-    for token in matching_tokens {
-        let sub_string = &towel[token.len()..];
-        if match_towel_recurse(sub_string, tokens) {
-            return true;
-        }
-    }
-    false
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
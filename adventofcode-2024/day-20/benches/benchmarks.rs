@@ -7,10 +7,10 @@ fn main() {
 
 #[divan::bench]
 fn part1() {
-    part1::process(divan::black_box(include_str!("../input.txt",)), Some(100)).unwrap();
+    part1::process(divan::black_box(include_str!("../input.txt",))).unwrap();
 }
 
 #[divan::bench]
 fn part2() {
-    part2::process(divan::black_box(include_str!("../input.txt",)), 100).unwrap();
+    part2::process(divan::black_box(include_str!("../input.txt",))).unwrap();
 }
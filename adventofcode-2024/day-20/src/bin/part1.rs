@@ -1,4 +1,4 @@
-use day_20::part1::{process, process_parameterized};
+use day_20::part1::process;
 use miette::Context;
 
 #[tracing::instrument]
@@ -1,14 +1,11 @@
-use crate::{find_path, parse, Racetrack, NEIGHBORS};
-use glam::IVec2;
-use itertools::Itertools;
+use crate::racetrack::Racetrack;
 use miette::miette;
 use nom::branch::alt;
 use nom::bytes::complete::tag;
 use nom::character::complete;
 use nom::combinator::{map, value};
-use nom::sequence::{delimited, separated_pair};
+use nom::sequence::delimited;
 use nom::IResult;
-use std::collections::HashMap;
 
 #[tracing::instrument]
 pub fn process(input: &str) -> miette::Result<String> {
@@ -31,7 +28,7 @@ pub fn process_with_args(input: &str, args: &str) -> miette::Result<String> {
     let (_, maybe_min_savings_limit) =
         parse_args(args).map_err(|e| miette!("arg-parse failed {}", e))?;
 
-    Ok(process_parameterized(input, maybe_min_savings_limit)?)
+    process_parameterized(input, maybe_min_savings_limit)
 }
 
 #[tracing::instrument]
@@ -39,65 +36,11 @@ pub fn process_parameterized(
     input: &str,
     min_savings_limit: Option<u32>,
 ) -> miette::Result<String> {
-    let racetrack = parse(input);
+    let racetrack = Racetrack::parse(input)?;
 
-    let (path, cost) =
-        find_path(&racetrack.walls, &racetrack.start, &racetrack.end).expect("path to be found");
+    let savings = racetrack.cheat_savings(2, min_savings_limit.unwrap_or(0))?;
 
-    let savings_map = find_number_of_cheats(path, &racetrack);
-
-    let relevant_entries = match min_savings_limit {
-        None => savings_map,
-        Some(lower_bound) => savings_map
-            .into_iter()
-            .filter(|(savings, _)| *savings >= lower_bound as i32)
-            .collect(),
-    };
-
-    let result = relevant_entries.values().sum::<u32>();
-
-    Ok(result.to_string())
-}
-
-fn find_number_of_cheats(path: Vec<IVec2>, racetrack: &Racetrack) -> HashMap<i32, u32> {
-    let visited_tiles: HashMap<IVec2, i32> = path
-        .iter()
-        .enumerate()
-        .map(|(idx, loc)| (*loc, idx as i32))
-        .collect();
-
-    // check the wall-neighbors of all wall-tiles (and their neighbors?) to see if they lead to a path-tile again.
-    // might work, because the problem statement said: "Because there is only a single path from the start to the end"
-
-    let mut savings_map: HashMap<i32, u32> = HashMap::new();
-
-    for (curr, next) in path.iter().tuple_windows() {
-        for wall_neighbor_1 in NEIGHBORS.map(|offset| curr + offset) {
-            if racetrack.walls.contains(&wall_neighbor_1) {
-                for neighbor_2 in NEIGHBORS.map(|offset| wall_neighbor_1 + offset) {
-                    if !racetrack.walls.contains(&neighbor_2)
-                        && visited_tiles.contains_key(&neighbor_2)
-                        && &neighbor_2 != curr
-                        && &neighbor_2 != next
-                    {
-                        let cost_current = visited_tiles[curr];
-                        let cost_neighbor_2_original = visited_tiles[&neighbor_2];
-
-                        let savings = cost_neighbor_2_original - cost_current - 2;
-                        if savings > 0 {
-                            savings_map
-                                .entry(savings)
-                                .and_modify(|e| *e += 1u32)
-                                .or_insert(1u32);
-                            //println!("shortcut from {curr} (cost {cost_current}) to {neighbor_2} (cost original {cost_neighbor_2_original}) saves {savings} by removing wall {wall_neighbor_1}.")
-                        }
-                    }
-                }
-            }
-        }
-    }
-
-    savings_map
+    Ok(savings.total().to_string())
 }
 
 #[cfg(test)]
@@ -144,33 +87,4 @@ mod tests {
         );
         Ok(())
     }
-
-    #[test]
-    fn pathfinding() {
-        let input = r#"
-###############
-#...#...#.....#
-#.#.#.#.#.###.#
-#S#...#.#.#...#
-#######.#.#.###
-#######.#.#...#
-#######.#.###.#
-###..E#...#...#
-###.#######.###
-#...###...#...#
-#.#####.#.###.#
-#.#...#.#.#...#
-#.#.#.#.#.#.###
-#...#...#...###
-###############
-        "#
-        .trim();
-
-        let racetrack = parse(input);
-
-        let (_path, cost) = find_path(&racetrack.walls, &racetrack.start, &racetrack.end)
-            .expect("path to be found");
-
-        assert_eq!(cost, 84);
-    }
 }
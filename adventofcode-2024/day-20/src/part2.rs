@@ -1,6 +1,4 @@
-use crate::{find_path, parse};
-use glam::IVec2;
-use itertools::Itertools;
+use crate::racetrack::Racetrack;
 use miette::miette;
 use nom::character::complete;
 use nom::IResult;
@@ -19,54 +17,16 @@ fn parse_args(args: &str) -> IResult<&str, u32> {
 pub fn process_with_args(input: &str, args: &str) -> miette::Result<String> {
     let (_, min_savings_limit) = parse_args(args).map_err(|e| miette!("arg-parse failed {}", e))?;
 
-    Ok(process_parameterized(input, min_savings_limit)?)
+    process_parameterized(input, min_savings_limit)
 }
 
 #[tracing::instrument]
 pub fn process_parameterized(input: &str, min_savings_limit: u32) -> miette::Result<String> {
-    let racetrack = parse(input);
+    let racetrack = Racetrack::parse(input)?;
 
-    let (path, cost) =
-        find_path(&racetrack.walls, &racetrack.start, &racetrack.end).expect("path to be found");
+    let savings = racetrack.cheat_savings(20, min_savings_limit)?;
 
-    let result = find_number_of_cheats(path, cost, min_savings_limit);
-
-    Ok(result.to_string())
-}
-
-fn find_number_of_cheats(
-    path: Vec<IVec2>,
-    complete_path_cost: u32,
-    min_savings_limit: u32,
-) -> usize {
-    let max_cheat_length = 20;
-
-    // - check every node in the path
-    //   - from there - check every succeeding node and check the manhattan distance
-    //   - if this distance is lower than the original costs at this point, we've found a cheat
-
-    let result = path
-        .iter()
-        .enumerate()
-        .tuple_combinations()
-        .filter_map(|((cost_n1, pos_n1), (cost_n2, pos_n2))| {
-            let manhattan_distance = (pos_n2 - pos_n1).abs().element_sum() as u32;
-
-            if manhattan_distance > max_cheat_length {
-                None
-            } else {
-                let original_cost_n2_to_destination = complete_path_cost - cost_n2 as u32;
-                let complete_costs_using_cheat =
-                    cost_n1 as u32 + manhattan_distance + original_cost_n2_to_destination;
-
-                let savings = complete_path_cost - complete_costs_using_cheat;
-
-                (savings >= min_savings_limit).then_some(complete_costs_using_cheat)
-            }
-        })
-        .count();
-
-    result
+    Ok(savings.total().to_string())
 }
 
 #[cfg(test)]
@@ -113,40 +73,10 @@ mod tests {
             .iter()
             .sum();
 
-        // I thought this was a valid testcase. My solution created the correct answer for part 2 but not for this testcase.
         assert_eq!(
             expected_number_of_cheats.to_string(),
             process_parameterized(input, 50)?
         );
         Ok(())
     }
-
-    #[test]
-    fn pathfinding() {
-        let input = r#"
-###############
-#...#...#.....#
-#.#.#.#.#.###.#
-#S#...#.#.#...#
-#######.#.#.###
-#######.#.#...#
-#######.#.###.#
-###..E#...#...#
-###.#######.###
-#...###...#...#
-#.#####.#.###.#
-#.#...#.#.#...#
-#.#.#.#.#.#.###
-#...#...#...###
-###############
-        "#
-        .trim();
-
-        let racetrack = parse(input);
-
-        let (_path, cost) = find_path(&racetrack.walls, &racetrack.start, &racetrack.end)
-            .expect("path to be found");
-
-        assert_eq!(cost, 84);
-    }
 }
@@ -0,0 +1,237 @@
+use glam::IVec2;
+use miette::miette;
+use rayon::prelude::*;
+use std::collections::{BTreeMap, VecDeque};
+
+const NEIGHBORS: [IVec2; 4] = [IVec2::NEG_Y, IVec2::X, IVec2::Y, IVec2::NEG_X];
+
+/// Number of cheats per picoseconds saved.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SavingsHistogram(BTreeMap<u32, usize>);
+
+impl SavingsHistogram {
+    /// Number of cheats saving exactly `saving` picoseconds.
+    pub fn get(&self, saving: u32) -> usize {
+        self.0.get(&saving).copied().unwrap_or(0)
+    }
+
+    /// Number of cheats over all savings.
+    pub fn total(&self) -> usize {
+        self.0.values().sum()
+    }
+
+    /// `(saving, count)` ordered by saving.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, usize)> + '_ {
+        self.0.iter().map(|(saving, count)| (*saving, *count))
+    }
+
+    fn merge(mut self, other: SavingsHistogram) -> SavingsHistogram {
+        for (saving, count) in other.0 {
+            *self.0.entry(saving).or_default() += count;
+        }
+        self
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Racetrack {
+    dimensions: IVec2,
+    walls: Vec<bool>,
+    start: IVec2,
+    end: IVec2,
+}
+
+impl Racetrack {
+    pub fn parse(input: &str) -> miette::Result<Racetrack> {
+        let lines = input.trim().lines().collect::<Vec<_>>();
+        let width = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let mut walls = vec![true; width * lines.len()];
+        let mut start = None;
+        let mut end = None;
+
+        for (y, row) in lines.iter().enumerate() {
+            for (x, char) in row.char_indices() {
+                let pos = IVec2::new(x as i32, y as i32);
+                match char {
+                    '#' => {}
+                    '.' | 'S' | 'E' => {
+                        walls[y * width + x] = false;
+                        let tile = match char {
+                            'S' => &mut start,
+                            'E' => &mut end,
+                            _ => continue,
+                        };
+                        if let Some(first) = tile.replace(pos) {
+                            return Err(miette!(
+                                "second '{char}' tile at {pos}, the first one is at {first}"
+                            ));
+                        }
+                    }
+                    tile => return Err(miette!("can't parse tile '{tile}' at {pos}")),
+                }
+            }
+        }
+
+        Ok(Racetrack {
+            dimensions: IVec2::new(width as i32, lines.len() as i32),
+            walls,
+            start: start.ok_or_else(|| miette!("no start tile 'S'"))?,
+            end: end.ok_or_else(|| miette!("no end tile 'E'"))?,
+        })
+    }
+
+    fn index(&self, pos: IVec2) -> Option<usize> {
+        (pos.cmpge(IVec2::ZERO).all() && pos.cmplt(self.dimensions).all())
+            .then(|| (pos.y * self.dimensions.x + pos.x) as usize)
+    }
+
+    fn position(&self, index: usize) -> IVec2 {
+        IVec2::new(
+            index as i32 % self.dimensions.x,
+            index as i32 / self.dimensions.x,
+        )
+    }
+
+    /// Picoseconds from `from` to every track tile without cheating, `None` for walls and unreachable tiles.
+    pub fn distances_from(&self, from: IVec2) -> Vec<Option<u32>> {
+        let mut distances = vec![None; self.walls.len()];
+        let Some(from_idx) = self.index(from).filter(|idx| !self.walls[*idx]) else {
+            return distances;
+        };
+        distances[from_idx] = Some(0);
+        let mut queue = VecDeque::from([(from, 0)]);
+
+        while let Some((pos, distance)) = queue.pop_front() {
+            for offset in NEIGHBORS {
+                let next = pos + offset;
+                if let Some(idx) = self.index(next) {
+                    if !self.walls[idx] && distances[idx].is_none() {
+                        distances[idx] = Some(distance + 1);
+                        queue.push_back((next, distance + 1));
+                    }
+                }
+            }
+        }
+        distances
+    }
+
+    /// Fastest time from start to end without cheating.
+    pub fn fastest_time(&self) -> Option<u32> {
+        self.distances_from(self.start)[self.index(self.end)?]
+    }
+
+    /// Every cheat of at most `max_cheat_duration` picoseconds that saves at least `min_saving` picoseconds.
+    /// A cheat is identified by its start and end position, so it can start on any tile reachable
+    /// from the start and end on any track tile within the manhattan distance that still reaches the end.
+    pub fn cheat_savings(
+        &self,
+        max_cheat_duration: u32,
+        min_saving: u32,
+    ) -> miette::Result<SavingsHistogram> {
+        let from_start = self.distances_from(self.start);
+        let to_end = self.distances_from(self.end);
+        let fastest = self
+            .index(self.end)
+            .and_then(|end| from_start[end])
+            .ok_or_else(|| miette!("no path from {} to {}", self.start, self.end))?;
+
+        let radius = max_cheat_duration as i32;
+        let offsets = (-radius..=radius)
+            .flat_map(|y| (-radius..=radius).map(move |x| IVec2::new(x, y)))
+            .filter(|offset| offset.abs().element_sum() <= radius)
+            .collect::<Vec<_>>();
+
+        let histogram = (0..self.walls.len())
+            .into_par_iter()
+            .filter_map(|idx| Some((idx, from_start[idx]?)))
+            .fold(
+                SavingsHistogram::default,
+                |mut histogram, (idx, to_cheat)| {
+                    let pos = self.position(idx);
+                    for offset in &offsets {
+                        let Some(from_cheat) =
+                            self.index(pos + *offset).and_then(|idx| to_end[idx])
+                        else {
+                            continue;
+                        };
+                        let time = to_cheat + offset.abs().element_sum() as u32 + from_cheat;
+                        let saving = fastest.saturating_sub(time);
+                        if saving > 0 && saving >= min_saving {
+                            *histogram.0.entry(saving).or_default() += 1;
+                        }
+                    }
+                    histogram
+                },
+            )
+            .reduce(SavingsHistogram::default, SavingsHistogram::merge);
+
+        Ok(histogram)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rstest::rstest;
+
+    const EXAMPLE: &str = "###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
+
+    #[test]
+    fn test_fastest_time() -> miette::Result<()> {
+        assert_eq!(Some(84), Racetrack::parse(EXAMPLE)?.fastest_time());
+        Ok(())
+    }
+
+    #[rstest]
+    #[case(2, 0, &[(2, 14), (4, 14), (6, 2), (8, 4), (10, 2), (12, 3), (20, 1), (36, 1), (38, 1), (40, 1), (64, 1)])]
+    #[case(20, 50, &[(50, 32), (52, 31), (54, 29), (56, 39), (58, 25), (60, 23), (62, 20), (64, 19), (66, 12), (68, 14), (70, 12), (72, 22), (74, 4), (76, 3)])]
+    fn test_cheat_savings(
+        #[case] max_cheat_duration: u32,
+        #[case] min_saving: u32,
+        #[case] expected: &[(u32, usize)],
+    ) -> miette::Result<()> {
+        let histogram = Racetrack::parse(EXAMPLE)?.cheat_savings(max_cheat_duration, min_saving)?;
+        assert_eq!(expected, histogram.iter().collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[test]
+    fn test_min_saving_filters_histogram() -> miette::Result<()> {
+        let racetrack = Racetrack::parse(EXAMPLE)?;
+        let histogram = racetrack.cheat_savings(2, 20)?;
+        assert_eq!(5, histogram.total());
+        assert_eq!(1, histogram.get(64));
+        assert_eq!(0, histogram.get(12));
+        Ok(())
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(Racetrack::parse("#S.x.E#").is_err());
+        assert!(Racetrack::parse("#S.S.E#").is_err());
+        assert!(Racetrack::parse("#S.E.E#").is_err());
+        assert!(Racetrack::parse("#S...E#")
+            .unwrap()
+            .cheat_savings(2, 0)
+            .is_ok());
+        // no path without cheating
+        assert!(Racetrack::parse("#S.#.E#")
+            .unwrap()
+            .cheat_savings(2, 0)
+            .is_err());
+    }
+}